@@ -2,6 +2,7 @@ import asyncio
 import logging
 import datetime
 import os
+import sys
 
 import local_secrets
 
@@ -9,9 +10,29 @@ from jinja2 import Environment, FileSystemLoader, select_autoescape
 from progress.bar import ShadyBar
 from pathlib import Path
 
-VERSION = 3
+VERSION = 4
 SOURCE_CARGO = Path("templates/Cargo.toml")
 
+# Version 3 named every variant by its binary code and did not version the
+# state. Each of its upgradeable variants gets this version under its name in
+# `processed/upgrade_v3_<code>/`, with `migrateV3` to convert the state. The
+# version 3 module is built from `templates/v3/` into `processed/v3_<code>/`
+# for the tests, and not deployed.
+LEGACY_VERSION = 3
+
+# Optional features outside of the binary code. The ones given on the command
+# line are generated into every variant. Each of them is also built and tested
+# on its own in the variant with every feature of the binary code, which is
+# generated into `processed/ext_<extension>/` and not deployed.
+EXTENSIONS = [
+    "multisig",
+    "receive_tokens",
+    "wrapped_ccd",
+    "dividends",
+    "snapshots",
+]
+EXTENSION_TEST_CODE = "111111"
+
 os.makedirs("logs", exist_ok=True)
 logging.basicConfig(level=logging.INFO, filename=f"logs/{datetime.datetime.now()}.log")
 
@@ -38,15 +59,15 @@ async def source_build(binary, bar):
 async def build_sources(bar):
     tasks = []
     semaphore = asyncio.Semaphore(4)
-    for i in range(0, 64):
-        binary = f"{i:06b}"
+    for binary in build_variants():
 
         async def task(binary=binary):
             async with semaphore:
-                await source_build(binary, bar)
+                return await source_build(binary, bar)
 
         tasks.append(asyncio.create_task(task()))
-    await asyncio.gather(*tasks)
+    results = await asyncio.gather(*tasks)
+    return [binary for binary, code in zip(build_variants(), results) if code != 0]
 
 
 async def test_run(binary, bar):
@@ -71,15 +92,15 @@ async def test_run(binary, bar):
 async def run_tests(bar):
     tasks = []
     semaphore = asyncio.Semaphore(8)
-    for i in range(0, 64):
-        binary = f"{i:06b}"
+    for binary in test_variants():
 
         async def task(binary=binary):
             async with semaphore:
-                await test_run(binary, bar)
+                return await test_run(binary, bar)
 
         tasks.append(asyncio.create_task(task()))
-    await asyncio.gather(*tasks)
+    results = await asyncio.gather(*tasks)
+    return [binary for binary, code in zip(test_variants(), results) if code != 0]
 
 
 async def contract_deploy(binary, bar):
@@ -123,48 +144,125 @@ async def contract_deploy(binary, bar):
 
 async def deploy_contracts(bar):
     tasks = []
-    for i in range(0, 64):
-        binary = f"{i:06b}"
+    for binary in variants():
         tasks.append(asyncio.ensure_future(contract_deploy(binary, bar)))
     await asyncio.gather(*tasks)
 
 
+def upgradeable_codes():
+    return [f"{i:06b}" for i in range(0, 64) if f"{i:06b}"[4] != "0"]
+
+
+def variants():
+    # The deployed variants.
+    return [f"{i:06b}" for i in range(0, 64)] + [f"upgrade_v3_{binary}" for binary in upgradeable_codes()]
+
+
+def test_variants():
+    return variants() + [f"ext_{name}" for name in EXTENSIONS]
+
+
+def build_variants():
+    return test_variants() + [f"v3_{binary}" for binary in upgradeable_codes()]
+
+
+def contract_name(binary):
+    # Upgradeable variants share a contract name, so an instance can be
+    # upgraded to any variant with a superset of its features.
+    if binary[4] != "0":
+        return f"mint_wizard_upgradeable_V{VERSION}"
+    return f"mint_wizard_{binary}_V{VERSION}"
+
+
+def upgradable_from(i):
+    # The upgradeable variants with a strict subset of the features of `i`.
+    return [
+        f"{j:06b}"
+        for j in range(0, 64)
+        if j & i == j and j != i and f"{j:06b}"[4] != "0"
+    ]
+
+
+def process_variant(env, directory, binary, extensions, migrate_v3=False):
+    context = {
+        "mintable":     binary[0] != "0",
+        "burnable":     binary[1] != "0",
+        "pausable":     binary[2] != "0",
+        "roles":        binary[3] != "0",
+        "updates":      binary[4] != "0",
+        "sponsored":    binary[5] != "0",
+        "code":         binary,
+        "version":      VERSION,
+        "contract_name": f"mint_wizard_{binary}_V{LEGACY_VERSION}" if migrate_v3 else contract_name(binary),
+        "upgradable_from": [] if migrate_v3 else upgradable_from(int(binary, 2)),
+        "migrate_v3":   migrate_v3,
+        **{name: name in extensions for name in EXTENSIONS},
+    }
+    source_result = env.get_template("src/lib.rs").render(context)
+    Path(f"processed/{directory}/src/").mkdir(parents=True, exist_ok=True)
+    with open(f"processed/{directory}/src/lib.rs", "w") as f:
+        f.writelines(source_result)
+    tests_result = env.get_template("tests/tests.rs").render(context)
+    Path(f"processed/{directory}/tests/").mkdir(parents=True, exist_ok=True)
+    with open(f"processed/{directory}/tests/tests.rs", "w") as f:
+        f.writelines(tests_result)
+    Path(f"processed/{directory}/Cargo.toml").write_text(SOURCE_CARGO.read_text())
+
+
+def process_legacy_variant(env, binary):
+    context = {
+        "mintable":     binary[0] != "0",
+        "burnable":     binary[1] != "0",
+        "pausable":     binary[2] != "0",
+        "roles":        binary[3] != "0",
+        "updates":      binary[4] != "0",
+        "sponsored":    binary[5] != "0",
+        "code":         binary,
+        "version":      LEGACY_VERSION,
+    }
+    source_result = env.get_template("v3/src/lib.rs").render(context)
+    Path(f"processed/v3_{binary}/src/").mkdir(parents=True, exist_ok=True)
+    with open(f"processed/v3_{binary}/src/lib.rs", "w") as f:
+        f.writelines(source_result)
+    Path(f"processed/v3_{binary}/Cargo.toml").write_text(SOURCE_CARGO.read_text())
+
+
 def main():
+    # With `--check`, every variant is rendered, built and tested, and nothing
+    # is deployed. It exits with an error if any of them fails.
+    check = "--check" in sys.argv[1:]
+    extensions = [arg for arg in sys.argv[1:] if arg != "--check"]
+    for name in extensions:
+        if name not in EXTENSIONS:
+            sys.exit(f"Unknown extension {name}, expected one of: {', '.join(EXTENSIONS)}")
     env = Environment(
         loader=FileSystemLoader("templates"), autoescape=select_autoescape()
     )
-    source_template = env.get_template("src/lib.rs")
-    tests_template = env.get_template("tests/tests.rs")
-    with ShadyBar("1 | Processing Variations\t", max=64) as bar:
+    with ShadyBar("1 | Processing Variations\t", max=len(build_variants())) as bar:
         for i in range(0, 64):
             binary = f"{i:06b}"
-            context = {
-                "mintable":     binary[0] != "0",
-                "burnable":     binary[1] != "0",
-                "pausable":     binary[2] != "0",
-                "roles":        binary[3] != "0",
-                "updates":      binary[4] != "0",
-                "sponsored":    binary[5] != "0",
-                "code":         binary,
-                "version":      VERSION,
-            }
-            source_result = source_template.render(context)
-            Path(f"processed/{binary}/src/").mkdir(parents=True, exist_ok=True)
-            with open(f"processed/{binary}/src/lib.rs", "w") as f:
-                f.writelines(source_result)
-            tests_result = tests_template.render(context)
-            Path(f"processed/{binary}/tests/").mkdir(parents=True, exist_ok=True)
-            with open(f"processed/{binary}/tests/tests.rs", "w") as f:
-                f.writelines(tests_result)
-            Path(f"processed/{binary}/Cargo.toml").write_text(SOURCE_CARGO.read_text())
+            process_variant(env, binary, binary, extensions)
+            bar.next()
+        for binary in upgradeable_codes():
+            process_variant(env, f"upgrade_v3_{binary}", binary, extensions, migrate_v3=True)
+            process_legacy_variant(env, binary)
+            bar.next()
+            bar.next()
+        for name in EXTENSIONS:
+            process_variant(env, f"ext_{name}", EXTENSION_TEST_CODE, [name])
             bar.next()
-    with ShadyBar("2 | Building Sources\t\t", max=64) as bar:
+    with ShadyBar("2 | Building Sources\t\t", max=len(build_variants())) as bar:
         loop = asyncio.get_event_loop()
-        loop.run_until_complete(build_sources(bar))
-    with ShadyBar("3 | Running Tests\t\t", max=64) as bar:
+        build_failures = loop.run_until_complete(build_sources(bar))
+    with ShadyBar("3 | Running Tests\t\t", max=len(test_variants())) as bar:
         loop = asyncio.get_event_loop()
-        loop.run_until_complete(run_tests(bar))
-    with ShadyBar("4 | Deploying Modules\t\t", max=64) as bar:
+        test_failures = loop.run_until_complete(run_tests(bar))
+    if check:
+        failures = build_failures + [binary for binary in test_failures if binary not in build_failures]
+        if failures:
+            sys.exit(f"Failed variants (see logs/): {', '.join(failures)}")
+        return
+    with ShadyBar("4 | Deploying Modules\t\t", max=len(variants())) as bar:
         loop = asyncio.get_event_loop()
         loop.run_until_complete(deploy_contracts(bar))
 
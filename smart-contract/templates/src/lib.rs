@@ -1,34 +1,131 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 use concordium_cis2::*;
+{% if sponsored or wrapped_ccd or receive_tokens %}
 use concordium_std::{collections::BTreeMap, EntrypointName, *};
+{% else %}
+use concordium_std::{collections::BTreeMap, *};
+{% endif %}
 
 const SUPPORTS_STANDARDS: [StandardIdentifier<'static>; 2] =
     [CIS0_STANDARD_IDENTIFIER, CIS2_STANDARD_IDENTIFIER];
 
-{% if sponsored %}
-const SUPPORTS_PERMIT_ENTRYPOINTS: [EntrypointName; 2] =
-    [EntrypointName::new_unchecked("updateOperator"), EntrypointName::new_unchecked("transfer")];
-{% endif %}
-
-/// Event tags.
+/// The version of the layout of `State`. Layouts of the same version differ
+/// only in the fields of the features a variant was generated with.
+pub const STATE_VERSION: u8 = 1;
+
+/// The features of this variant. The low bits are in the order of the binary
+/// code, with mintable as the most significant of them.
+pub const FEATURES: u32 = 0b{{ code }}{% if multisig %} | FEATURE_MULTISIG{% endif %}{% if receive_tokens %} | FEATURE_RECEIVE_TOKENS{% endif %}{% if wrapped_ccd %} | FEATURE_WRAPPED_CCD{% endif %}{% if dividends %} | FEATURE_DIVIDENDS{% endif %}{% if snapshots %} | FEATURE_SNAPSHOTS{% endif %};
+
+/// Feature bits. Extensions outside of the binary code follow the bits of the
+/// code.
+pub const FEATURE_MINTABLE: u32 = 0b100000;
+pub const FEATURE_BURNABLE: u32 = 0b010000;
+pub const FEATURE_PAUSABLE: u32 = 0b001000;
+pub const FEATURE_ROLES: u32 = 0b000100;
+pub const FEATURE_UPDATES: u32 = 0b000010;
+pub const FEATURE_SPONSORED: u32 = 0b000001;
+pub const FEATURE_MULTISIG: u32 = 1 << 6;
+pub const FEATURE_RECEIVE_TOKENS: u32 = 1 << 7;
+pub const FEATURE_WRAPPED_CCD: u32 = 1 << 8;
+pub const FEATURE_DIVIDENDS: u32 = 1 << 9;
+pub const FEATURE_SNAPSHOTS: u32 = 1 << 10;
+
+// Event tags.
 {% if roles %}
 pub const GRANT_ROLE_EVENT_TAG: u8 = 1;
 pub const REVOKE_ROLE_EVENT_TAG: u8 = 2;
 {% endif %}
+{% if updates %}
+pub const UPGRADE_PROPOSED_EVENT_TAG: u8 = 32;
+pub const UPGRADE_CANCELLED_EVENT_TAG: u8 = 33;
+pub const UPGRADE_DELAY_UPDATED_EVENT_TAG: u8 = 49;
+pub const UPGRADE_ALLOWLIST_UPDATED_EVENT_TAG: u8 = 50;
+{% endif %}
+{% if multisig %}
+pub const OPERATION_PROPOSED_EVENT_TAG: u8 = 34;
+pub const OPERATION_APPROVED_EVENT_TAG: u8 = 35;
+pub const OPERATION_EXECUTED_EVENT_TAG: u8 = 36;
+{% endif %}
+pub const METADATA_BASE_UPDATED_EVENT_TAG: u8 = 40;
+pub const REVEAL_PREPARED_EVENT_TAG: u8 = 41;
+pub const ATTRIBUTES_UPDATED_EVENT_TAG: u8 = 42;
+{% if receive_tokens %}
+pub const TOKENS_RECEIVED_EVENT_TAG: u8 = 44;
+pub const TOKENS_RECOVERED_EVENT_TAG: u8 = 45;
+{% endif %}
+{% if dividends %}
+pub const DIVIDEND_DISTRIBUTED_EVENT_TAG: u8 = 46;
+pub const DIVIDEND_CLAIMED_EVENT_TAG: u8 = 47;
+{% endif %}
+{% if snapshots %}
+pub const SNAPSHOT_EVENT_TAG: u8 = 48;
+{% endif %}
+{% if mintable %}
+pub const MINTING_FINALIZED_EVENT_TAG: u8 = 37;
+pub const MAX_SUPPLY_UPDATED_EVENT_TAG: u8 = 38;
+pub const MAX_SUPPLY_LOCKED_EVENT_TAG: u8 = 39;
+{% endif %}
 {% if sponsored %}
 pub const NONCE_EVENT_TAG: u8 = 250;
+pub const PUBLIC_KEY_NONCE_EVENT_TAG: u8 = 43;
 {% endif %}
 
 {% if sponsored %}
+/// The domain of permit messages signed for this variant. Messages signed for
+/// another variant or version, or before the domain was added, are rejected.
+pub const PERMIT_DOMAIN: &str = "mint_wizard_{{ code }}_V{{ version }}";
+
 const TRANSFER_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("transfer");
 const UPDATE_OPERATOR_ENTRYPOINT: EntrypointName<'_> =
     EntrypointName::new_unchecked("updateOperator");
+const BUMP_NONCE_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("bumpNonce");
+const PERMIT_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("permit");
 {% if mintable %}
 const MINT_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("mint");
 {% endif %}
 {% if burnable %}
 const BURN_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("burn");
 {% endif %}
+{% if roles %}
+const GRANT_ROLE_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("grantRole");
+const REVOKE_ROLE_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("revokeRole");
+{% endif %}
+{% if pausable %}
+const SET_PAUSED_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("setPaused");
+{% endif %}
+{% if updates %}
+const UPGRADE_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("upgrade");
+{% endif %}
+{% if wrapped_ccd %}
+const UNWRAP_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("unwrap");
+{% endif %}
+
+/// The entrypoints `permit` dispatches to, depending on the features.
+const SUPPORTS_PERMIT_ENTRYPOINTS: &[EntrypointName<'_>] = &[
+    UPDATE_OPERATOR_ENTRYPOINT,
+    TRANSFER_ENTRYPOINT,
+    BUMP_NONCE_ENTRYPOINT,
+    {% if mintable %}
+    MINT_ENTRYPOINT,
+    {% endif %}
+    {% if burnable %}
+    BURN_ENTRYPOINT,
+    {% endif %}
+    {% if roles %}
+    GRANT_ROLE_ENTRYPOINT,
+    REVOKE_ROLE_ENTRYPOINT,
+    {% endif %}
+    {% if pausable %}
+    SET_PAUSED_ENTRYPOINT,
+    {% endif %}
+    {% if updates %}
+    UPGRADE_ENTRYPOINT,
+    {% endif %}
+    {% if wrapped_ccd %}
+    UNWRAP_ENTRYPOINT,
+    {% endif %}
+];
 {% endif %}
 
 #[derive(Debug, Serial, Deserial, PartialEq, Eq)]
@@ -40,14 +137,90 @@ pub enum Event {
     #[concordium(tag = 1)]
     RevokeRole(RevokeRoleEvent),
     {% endif %}
+    {% if updates %}
+    /// The event is logged when an upgrade is proposed.
+    #[concordium(tag = 32)]
+    UpgradeProposed(UpgradeProposedEvent),
+    /// The event is logged when a proposed upgrade is cancelled.
+    #[concordium(tag = 33)]
+    UpgradeCancelled(UpgradeCancelledEvent),
+    /// The event is logged when the upgrade delay is updated.
+    #[concordium(tag = 49)]
+    UpgradeDelayUpdated(UpgradeDelayUpdatedEvent),
+    /// The event is logged when a module is added to or removed from the
+    /// upgrade allowlist.
+    #[concordium(tag = 50)]
+    UpgradeAllowlistUpdated(UpgradeAllowlistUpdatedEvent),
+    {% endif %}
+    {% if multisig %}
+    /// The event is logged when a multisig operation is proposed.
+    #[concordium(tag = 34)]
+    OperationProposed(OperationProposedEvent),
+    /// The event is logged when a signer approves a multisig operation.
+    #[concordium(tag = 35)]
+    OperationApproved(OperationApprovedEvent),
+    /// The event is logged when a multisig operation reached the threshold
+    /// and was executed.
+    #[concordium(tag = 36)]
+    OperationExecuted(OperationExecutedEvent),
+    {% endif %}
+    {% if mintable %}
+    /// The event is logged when minting is finalized for all tokens or a
+    /// single token.
+    #[concordium(tag = 37)]
+    MintingFinalized(MintingFinalizedEvent),
+    /// The event is logged when the max supply of a token is updated.
+    #[concordium(tag = 38)]
+    MaxSupplyUpdated(MaxSupplyUpdatedEvent),
+    /// The event is logged when the max supply of a token is locked.
+    #[concordium(tag = 39)]
+    MaxSupplyLocked(MaxSupplyLockedEvent),
+    {% endif %}
+    /// The event is logged when the base URL of token metadata is updated.
+    #[concordium(tag = 40)]
+    MetadataBaseUpdated(MetadataBase),
+    /// The event is logged when a reveal is prepared.
+    #[concordium(tag = 41)]
+    RevealPrepared(PendingReveal),
+    /// The event is logged when attributes of a token are updated.
+    #[concordium(tag = 42)]
+    AttributesUpdated(SetAttributesParams),
+    {% if receive_tokens %}
+    /// The event is logged when tokens of another CIS-2 contract are received.
+    #[concordium(tag = 44)]
+    TokensReceived(TokensReceivedEvent),
+    /// The event is logged when tokens of another CIS-2 contract are
+    /// recovered.
+    #[concordium(tag = 45)]
+    TokensRecovered(TokensRecoveredEvent),
+    {% endif %}
+    {% if dividends %}
+    /// The event is logged when CCD is distributed to the holders of a token.
+    #[concordium(tag = 46)]
+    DividendDistributed(DividendDistributedEvent),
+    /// The event is logged when a holder claims dividends.
+    #[concordium(tag = 47)]
+    DividendClaimed(DividendClaimedEvent),
+    {% endif %}
+    {% if snapshots %}
+    /// The event is logged when a snapshot of the balances is taken.
+    #[concordium(tag = 48)]
+    Snapshot(SnapshotEvent),
+    {% endif %}
     {% if sponsored %}
     /// Cis3 event.
     /// The event tracks the nonce used by the signer of the `PermitMessage`
     /// whenever the `permit` function is invoked.
     #[concordium(tag = 250)]
     Nonce(NonceEvent),
+    /// The event tracks the nonce used by a public key signing a
+    /// `PermitMessage` whenever the `permitPublicKey` function is invoked.
+    #[concordium(tag = 43)]
+    PublicKeyNonce(PublicKeyNonceEvent),
     {% endif %}
-    /// Cis2 token events.
+    /// Cis2 token events. The derive reserves the index of this variant, so
+    /// the tags of the events following the roles events start at 32, above
+    /// the number of variants.
     #[concordium(forward = cis2_events)]
     Cis2Event(Cis2Event<ContractTokenId, ContractTokenAmount>),
 }
@@ -58,6 +231,12 @@ pub struct NonceEvent {
     pub account: AccountAddress,
     pub nonce:   u64,
 }
+
+#[derive(Debug, Serialize, SchemaType, PartialEq, Eq)]
+pub struct PublicKeyNonceEvent {
+    pub public_key: PublicKeyEd25519,
+    pub nonce:      u64,
+}
 {% endif %}
 
 {% if roles %}
@@ -65,6 +244,8 @@ pub struct NonceEvent {
 pub struct GrantRoleEvent {
     pub address: Address,
     pub role:    Roles,
+    /// The time the role expires at, if any.
+    pub expiry:  Option<Timestamp>,
 }
 
 /// The RevokeRoleEvent is logged when a role is revoked from an address.
@@ -75,6 +256,132 @@ pub struct RevokeRoleEvent {
 }
 {% endif %}
 
+{% if updates %}
+/// The UpgradeProposedEvent is logged when an upgrade is proposed.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct UpgradeProposedEvent {
+    pub module:           ModuleReference,
+    pub executable_after: Timestamp,
+}
+
+/// The UpgradeCancelledEvent is logged when a proposed upgrade is cancelled.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct UpgradeCancelledEvent {
+    pub module: ModuleReference,
+}
+
+/// The UpgradeDelayUpdatedEvent is logged when the upgrade delay is updated.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct UpgradeDelayUpdatedEvent {
+    pub delay: Duration,
+}
+
+/// The UpgradeAllowlistUpdatedEvent is logged when a module is added to or
+/// removed from the upgrade allowlist.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct UpgradeAllowlistUpdatedEvent {
+    pub module:  ModuleReference,
+    pub allowed: bool,
+}
+{% endif %}
+
+{% if multisig %}
+/// The OperationProposedEvent is logged when a multisig operation is proposed.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct OperationProposedEvent {
+    pub operation_id: u64,
+    pub proposer:     AccountAddress,
+    pub entrypoint:   OwnedEntrypointName,
+}
+
+/// The OperationApprovedEvent is logged when a signer approves a multisig
+/// operation.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct OperationApprovedEvent {
+    pub operation_id: u64,
+    pub signer:       AccountAddress,
+}
+
+/// The OperationExecutedEvent is logged when a multisig operation is executed.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct OperationExecutedEvent {
+    pub operation_id: u64,
+}
+{% endif %}
+
+{% if receive_tokens %}
+/// The TokensReceivedEvent is logged when tokens of another CIS-2 contract are
+/// received.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct TokensReceivedEvent {
+    pub contract: ContractAddress,
+    pub token_id: TokenIdVec,
+    pub amount:   ReceivedTokenAmount,
+    pub from:     Address,
+}
+
+/// The TokensRecoveredEvent is logged when tokens of another CIS-2 contract
+/// are transferred out by `recoverTokens`.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct TokensRecoveredEvent {
+    pub contract: ContractAddress,
+    pub token_id: TokenIdVec,
+    pub amount:   ReceivedTokenAmount,
+    pub to:       Address,
+}
+{% endif %}
+
+{% if dividends %}
+/// The DividendDistributedEvent is logged when CCD is distributed to the
+/// holders of a token.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct DividendDistributedEvent {
+    pub token_id: ContractTokenId,
+    pub amount:   Amount,
+    pub from:     Address,
+}
+
+/// The DividendClaimedEvent is logged when a holder claims dividends.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct DividendClaimedEvent {
+    pub token_id: ContractTokenId,
+    pub amount:   Amount,
+    pub holder:   Address,
+}
+{% endif %}
+
+{% if snapshots %}
+/// The SnapshotEvent is logged when a snapshot of the balances is taken.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct SnapshotEvent {
+    pub snapshot_id: u64,
+}
+{% endif %}
+
+{% if mintable %}
+/// The MintingFinalizedEvent is logged when minting is finalized. A `None`
+/// token ID finalizes minting of all tokens.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct MintingFinalizedEvent {
+    pub token_id: Option<ContractTokenId>,
+}
+
+/// The MaxSupplyUpdatedEvent is logged when the max supply of a token is
+/// updated.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct MaxSupplyUpdatedEvent {
+    pub token_id:   ContractTokenId,
+    pub max_supply: ContractTokenAmount,
+}
+
+/// The MaxSupplyLockedEvent is logged when the max supply of a token can no
+/// longer be updated.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub struct MaxSupplyLockedEvent {
+    pub token_id: ContractTokenId,
+}
+{% endif %}
+
 impl schema::SchemaType for Event {
     fn get_type() -> schema::Type {
         let mut event_map = BTreeMap::new();
@@ -89,6 +396,16 @@ impl schema::SchemaType for Event {
                 ]),
             ),
         );
+        event_map.insert(
+            PUBLIC_KEY_NONCE_EVENT_TAG,
+            (
+                "PublicKeyNonce".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("public_key"), PublicKeyEd25519::get_type()),
+                    (String::from("nonce"), u64::get_type()),
+                ]),
+            ),
+        );
         {% endif %}
         {% if roles %}
         event_map.insert(
@@ -98,6 +415,7 @@ impl schema::SchemaType for Event {
                 schema::Fields::Named(vec![
                     (String::from("address"), Address::get_type()),
                     (String::from("role"), Roles::get_type()),
+                    (String::from("expiry"), Option::<Timestamp>::get_type()),
                 ]),
             ),
         );
@@ -112,6 +430,194 @@ impl schema::SchemaType for Event {
             ),
         );
         {% endif %}
+        {% if updates %}
+        event_map.insert(
+            UPGRADE_PROPOSED_EVENT_TAG,
+            (
+                "UpgradeProposed".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("module"), ModuleReference::get_type()),
+                    (String::from("executable_after"), Timestamp::get_type()),
+                ]),
+            ),
+        );
+        event_map.insert(
+            UPGRADE_CANCELLED_EVENT_TAG,
+            (
+                "UpgradeCancelled".to_string(),
+                schema::Fields::Named(vec![(String::from("module"), ModuleReference::get_type())]),
+            ),
+        );
+        event_map.insert(
+            UPGRADE_DELAY_UPDATED_EVENT_TAG,
+            (
+                "UpgradeDelayUpdated".to_string(),
+                schema::Fields::Named(vec![(String::from("delay"), Duration::get_type())]),
+            ),
+        );
+        event_map.insert(
+            UPGRADE_ALLOWLIST_UPDATED_EVENT_TAG,
+            (
+                "UpgradeAllowlistUpdated".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("module"), ModuleReference::get_type()),
+                    (String::from("allowed"), bool::get_type()),
+                ]),
+            ),
+        );
+        {% endif %}
+        {% if mintable %}
+        event_map.insert(
+            MINTING_FINALIZED_EVENT_TAG,
+            (
+                "MintingFinalized".to_string(),
+                schema::Fields::Named(vec![(
+                    String::from("token_id"),
+                    Option::<ContractTokenId>::get_type(),
+                )]),
+            ),
+        );
+        event_map.insert(
+            MAX_SUPPLY_UPDATED_EVENT_TAG,
+            (
+                "MaxSupplyUpdated".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("token_id"), ContractTokenId::get_type()),
+                    (String::from("max_supply"), ContractTokenAmount::get_type()),
+                ]),
+            ),
+        );
+        event_map.insert(
+            MAX_SUPPLY_LOCKED_EVENT_TAG,
+            (
+                "MaxSupplyLocked".to_string(),
+                schema::Fields::Named(vec![(String::from("token_id"), ContractTokenId::get_type())]),
+            ),
+        );
+        {% endif %}
+        {% if multisig %}
+        event_map.insert(
+            OPERATION_PROPOSED_EVENT_TAG,
+            (
+                "OperationProposed".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("operation_id"), u64::get_type()),
+                    (String::from("proposer"), AccountAddress::get_type()),
+                    (String::from("entrypoint"), OwnedEntrypointName::get_type()),
+                ]),
+            ),
+        );
+        event_map.insert(
+            OPERATION_APPROVED_EVENT_TAG,
+            (
+                "OperationApproved".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("operation_id"), u64::get_type()),
+                    (String::from("signer"), AccountAddress::get_type()),
+                ]),
+            ),
+        );
+        event_map.insert(
+            OPERATION_EXECUTED_EVENT_TAG,
+            (
+                "OperationExecuted".to_string(),
+                schema::Fields::Named(vec![(String::from("operation_id"), u64::get_type())]),
+            ),
+        );
+        {% endif %}
+        {% if receive_tokens %}
+        event_map.insert(
+            TOKENS_RECEIVED_EVENT_TAG,
+            (
+                "TokensReceived".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("contract"), ContractAddress::get_type()),
+                    (String::from("token_id"), TokenIdVec::get_type()),
+                    (String::from("amount"), ReceivedTokenAmount::get_type()),
+                    (String::from("from"), Address::get_type()),
+                ]),
+            ),
+        );
+        event_map.insert(
+            TOKENS_RECOVERED_EVENT_TAG,
+            (
+                "TokensRecovered".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("contract"), ContractAddress::get_type()),
+                    (String::from("token_id"), TokenIdVec::get_type()),
+                    (String::from("amount"), ReceivedTokenAmount::get_type()),
+                    (String::from("to"), Address::get_type()),
+                ]),
+            ),
+        );
+        {% endif %}
+        {% if dividends %}
+        event_map.insert(
+            DIVIDEND_DISTRIBUTED_EVENT_TAG,
+            (
+                "DividendDistributed".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("token_id"), ContractTokenId::get_type()),
+                    (String::from("amount"), Amount::get_type()),
+                    (String::from("from"), Address::get_type()),
+                ]),
+            ),
+        );
+        event_map.insert(
+            DIVIDEND_CLAIMED_EVENT_TAG,
+            (
+                "DividendClaimed".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("token_id"), ContractTokenId::get_type()),
+                    (String::from("amount"), Amount::get_type()),
+                    (String::from("holder"), Address::get_type()),
+                ]),
+            ),
+        );
+        {% endif %}
+        {% if snapshots %}
+        event_map.insert(
+            SNAPSHOT_EVENT_TAG,
+            (
+                "Snapshot".to_string(),
+                schema::Fields::Named(vec![(String::from("snapshot_id"), u64::get_type())]),
+            ),
+        );
+        {% endif %}
+        event_map.insert(
+            METADATA_BASE_UPDATED_EVENT_TAG,
+            (
+                "MetadataBaseUpdated".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("url"), String::get_type()),
+                    (String::from("suffix"), String::get_type()),
+                ]),
+            ),
+        );
+        event_map.insert(
+            REVEAL_PREPARED_EVENT_TAG,
+            (
+                "RevealPrepared".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("placeholder"), MetadataUrl::get_type()),
+                    (String::from("commitment"), Option::<HashSha2256>::get_type()),
+                ]),
+            ),
+        );
+        event_map.insert(
+            ATTRIBUTES_UPDATED_EVENT_TAG,
+            (
+                "AttributesUpdated".to_string(),
+                schema::Fields::Named(vec![
+                    (String::from("token_id"), ContractTokenId::get_type()),
+                    (
+                        String::from("attributes"),
+                        Vec::<(String, Option<String>)>::get_type(),
+                    ),
+                    (String::from("refresh_metadata"), bool::get_type()),
+                ]),
+            ),
+        );
         event_map.insert(
             TRANSFER_EVENT_TAG,
             (
@@ -179,52 +685,219 @@ pub type ContractTokenId = TokenIdU8;
 
 pub type ContractTokenAmount = TokenAmountU64;
 
+{% if wrapped_ccd %}
+/// The token minted one-to-one with CCD deposited by `wrap`.
+pub const WRAPPED_TOKEN_ID: ContractTokenId = TokenIdU8(0);
+{% endif %}
+
+{% if dividends %}
+/// The scale of dividends per token unit, so that distributions smaller than
+/// the supply still count.
+const DIVIDEND_MAGNITUDE: u128 = 1 << 64;
+
+/// The dividends distributed to the holders of a token.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DividendPool {
+    /// The cumulative dividends per token unit in micro CCD, scaled by
+    /// `DIVIDEND_MAGNITUDE`.
+    pub per_unit:  u128,
+    /// The part of the distributions that did not divide by the supply, scaled
+    /// by `DIVIDEND_MAGNITUDE`. It is added to the next distribution.
+    pub remainder: u128,
+}
+
+/// The dividends of a holder of a token.
+#[derive(Serialize, Clone, Copy)]
+struct DividendAccount {
+    /// `DividendPool::per_unit` when the dividends of the holder were last
+    /// settled.
+    paid_per_unit: u128,
+    /// The settled dividends that are not claimed yet.
+    owed:          Amount,
+}
+{% endif %}
+
 #[derive(Serial, Deserial, SchemaType)]
 pub struct TokenParams {
     pub amount: TokenAmountU64,
     pub max_supply: ContractTokenAmount,
 }
 
-#[derive(Serialize, SchemaType)]
-pub struct InitParams {
-    pub premint_tokens: collections::BTreeMap<ContractTokenId, (MetadataUrl, TokenParams)>,
+/// The base URL of token metadata. Tokens minted with an empty metadata URL get
+/// the URL `url + hex(token_id) + suffix`.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq, Eq)]
+pub struct MetadataBase {
+    pub url:    String,
+    pub suffix: String,
 }
 
-{% if mintable %}
-#[derive(Serialize, SchemaType)]
-pub struct MintParams {
-    pub owner:          Address,
-    pub tokens:         collections::BTreeMap<ContractTokenId, (MetadataUrl, TokenParams)>,
+/// A reveal that has not happened yet. Until then, tokens stored with an empty
+/// metadata URL show the placeholder.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq, Eq)]
+pub struct PendingReveal {
+    pub placeholder: MetadataUrl,
+    /// The SHA2-256 hash of the serialized `MetadataBase` the reveal must set.
+    pub commitment:  Option<HashSha2256>,
 }
-{% endif %}
 
-{% if burnable %}
-#[derive(Serialize, SchemaType)]
-pub struct BurnParams {
-    pub owner:          Address,
-    pub token_id:       ContractTokenId,
-    pub amount:         ContractTokenAmount,
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq, Eq)]
+pub struct SetAttributesParams {
+    pub token_id:         ContractTokenId,
+    /// The attributes to set. A `None` value removes the attribute.
+    pub attributes:       Vec<(String, Option<String>)>,
+    /// Whether to log a `TokenMetadata` event, so wallets fetch the metadata of
+    /// the token again.
+    pub refresh_metadata: bool,
 }
-{% endif %}
 
-{% if sponsored %}
-#[derive(Debug, Serialize, SchemaType)]
-pub struct SupportsPermitQueryParams {
-    #[concordium(size_length = 2)]
-    pub queries: Vec<OwnedEntrypointName>,
+/// The attributes of each queried token.
+pub type TokenAttributesQueryResponse = Vec<Vec<(String, String)>>;
+
+#[derive(Serialize, SchemaType)]
+pub struct SetTokenMetadataParams {
+    pub token_id:     ContractTokenId,
+    /// The metadata URL of the token. An empty URL uses the base URL.
+    pub metadata_url: MetadataUrl,
 }
-{% endif %}
 
-#[derive(Debug, Serialize, SchemaType)]
-struct SetImplementorsParams {
-    id:           StandardIdentifierOwned,
-    /// The addresses of the implementors of the standard.
-    implementors: Vec<ContractAddress>,
+/// The parameter of `init`. The fields after `premint_tokens` are optional,
+/// see `InitParams::parse`.
+#[derive(Serial, SchemaType)]
+pub struct InitParams {
+    pub premint_tokens:     collections::BTreeMap<ContractTokenId, (MetadataUrl, TokenParams)>,
+    /// Recipients of parts of the preminted amount of a token. The rest goes
+    /// to the owner.
+    pub premint_recipients: collections::BTreeMap<ContractTokenId, Vec<(Address, ContractTokenAmount)>>,
+    /// The owner of the contract{% if roles %}, who is granted the ADMIN role{% endif %}. Defaults to the
+    /// account initializing the contract.
+    pub owner:              Option<Address>,
+    /// The base URL of token metadata, for tokens with an empty metadata URL.
+    pub metadata_base:      Option<MetadataBase>,
+    {% if roles %}
+    /// Roles granted in addition to the ADMIN role of the owner.
+    pub roles:              Vec<(Address, Roles)>,
+    {% endif %}
+    {% if wrapped_ccd %}
+    /// The metadata URL of the wrapped CCD token.
+    pub wrapped_metadata:   MetadataUrl,
+    {% endif %}
 }
 
-{% if sponsored %}
-#[derive(SchemaType, Serialize)]
+impl InitParams {
+    /// Parse the parameter of `init`. Only `premint_tokens` is required, so
+    /// clients sending just the tokens can still initialize the contract. A
+    /// field can only be left out together with all fields after it.
+    fn parse<R: Read + Seek + HasSize>(cursor: &mut R) -> ParseResult<Self> {
+        fn has_more<R: Seek + HasSize>(cursor: &R) -> bool {
+            cursor.cursor_position() < cursor.size()
+        }
+
+        let mut params = InitParams {
+            premint_tokens:     cursor.get()?,
+            premint_recipients: collections::BTreeMap::new(),
+            owner:              None,
+            metadata_base:      None,
+            {% if roles %}
+            roles:              Vec::new(),
+            {% endif %}
+            {% if wrapped_ccd %}
+            // Without a URL, the wrapped token gets it from the metadata base.
+            wrapped_metadata:   MetadataUrl {
+                url:  String::new(),
+                hash: None,
+            },
+            {% endif %}
+        };
+        if has_more(cursor) {
+            params.premint_recipients = cursor.get()?;
+        }
+        if has_more(cursor) {
+            params.owner = cursor.get()?;
+        }
+        if has_more(cursor) {
+            params.metadata_base = cursor.get()?;
+        }
+        {% if roles %}
+        if has_more(cursor) {
+            params.roles = cursor.get()?;
+        }
+        {% endif %}
+        {% if wrapped_ccd %}
+        if has_more(cursor) {
+            params.wrapped_metadata = cursor.get()?;
+        }
+        {% endif %}
+        Ok(params)
+    }
+}
+
+{% if mintable %}
+#[derive(Serialize, SchemaType)]
+pub struct MintNextParams {
+    pub owner:  Address,
+    /// The tokens to mint, which are given the next free token IDs in order.
+    #[concordium(size_length = 2)]
+    pub tokens: Vec<(MetadataUrl, TokenParams)>,
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct UpdateMaxSupplyParams {
+    pub token_id:   ContractTokenId,
+    pub max_supply: ContractTokenAmount,
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct MintParams {
+    pub owner:          Address,
+    pub tokens:         collections::BTreeMap<ContractTokenId, (MetadataUrl, TokenParams)>,
+}
+{% endif %}
+
+{% if burnable %}
+#[derive(Serialize, SchemaType)]
+pub struct BurnParams {
+    pub owner:          Address,
+    pub token_id:       ContractTokenId,
+    pub amount:         ContractTokenAmount,
+}
+{% endif %}
+
+{% if sponsored %}
+#[derive(Debug, Serialize, SchemaType)]
+pub struct SupportsPermitQueryParams {
+    #[concordium(size_length = 2)]
+    pub queries: Vec<OwnedEntrypointName>,
+}
+
+#[derive(Serialize, SchemaType)]
+#[concordium(transparent)]
+pub struct VecOfAccountAddresses {
+    #[concordium(size_length = 2)]
+    pub queries: Vec<AccountAddress>,
+}
+
+/// The next nonce of each queried account.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+#[concordium(transparent)]
+pub struct NonceOfQueryResponse(#[concordium(size_length = 2)] pub Vec<u64>);
+
+impl From<Vec<u64>> for NonceOfQueryResponse {
+    fn from(results: Vec<u64>) -> Self { NonceOfQueryResponse(results) }
+}
+{% endif %}
+
+#[derive(Debug, Serialize, SchemaType)]
+struct SetImplementorsParams {
+    id:           StandardIdentifierOwned,
+    /// The addresses of the implementors of the standard.
+    implementors: Vec<ContractAddress>,
+}
+
+{% if sponsored %}
+#[derive(SchemaType, Serialize)]
 pub struct PermitMessage {
+    /// The domain that the signature is intended for, see `PERMIT_DOMAIN`.
+    pub domain:           String,
     /// The contract_address that the signature is intended for.
     pub contract_address: ContractAddress,
     /// A nonce to prevent replay attacks.
@@ -233,10 +906,18 @@ pub struct PermitMessage {
     pub timestamp:        Timestamp,
     /// The entry_point that the signature is intended for.
     pub entry_point:      OwnedEntrypointName,
-    /// The serialized payload that should be forwarded to either the `transfer`
-    /// or the `updateOperator` function.
+    /// The serialized payload that should be forwarded to the entry_point.
     #[concordium(size_length = 2)]
     pub payload:          Vec<u8>,
+    /// A fee the signer pays to the sponsor, i.e. the account that sent the
+    /// transaction.
+    pub fee:              Option<PermitFee>,
+}
+
+#[derive(SchemaType, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PermitFee {
+    pub token_id: ContractTokenId,
+    pub amount:   ContractTokenAmount,
 }
 
 #[derive(Serialize, SchemaType)]
@@ -246,6 +927,58 @@ pub struct PermitParam {
     pub message:   PermitMessage,
 }
 
+/// A permit signed with a public key registered with `registerPublicKey`,
+/// which acts for the address the key is registered to.
+#[derive(Serialize, SchemaType)]
+pub struct PermitPublicKeyParam {
+    pub signature: SignatureEd25519,
+    pub signer:    PublicKeyEd25519,
+    pub message:   PermitMessage,
+}
+
+/// Register a public key to the sender. The signature is of the serialized
+/// contract address and sender by the key, proving its possession.
+#[derive(Serialize, SchemaType)]
+pub struct RegisterPublicKeyParams {
+    pub public_key: PublicKeyEd25519,
+    pub signature:  SignatureEd25519,
+}
+
+/// The signer of a permit message.
+#[derive(Clone, Copy)]
+enum PermitSigner {
+    Account(AccountAddress),
+    /// A public key and the address it is registered to.
+    PublicKey(PublicKeyEd25519, Address),
+}
+
+impl PermitSigner {
+    /// The address the permit acts for.
+    fn address(&self) -> Address {
+        match self {
+            PermitSigner::Account(account) => Address::Account(*account),
+            PermitSigner::PublicKey(_, address) => *address,
+        }
+    }
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct PermitBatchParams {
+    /// Whether the batch fails if any permit fails. Otherwise failed permits
+    /// are skipped.
+    pub atomic:  bool,
+    #[concordium(size_length = 2)]
+    pub permits: Vec<PermitParam>,
+}
+
+/// The outcome of a permit in a batch.
+#[derive(Serialize, SchemaType, Debug, PartialEq, Eq)]
+pub enum PermitResult {
+    Executed,
+    /// The permit was rejected with the error code.
+    Failed(i32),
+}
+
 #[derive(Serialize)]
 pub struct PermitParamPartial {
     pub signature: AccountSignatures,
@@ -253,12 +986,138 @@ pub struct PermitParamPartial {
 }
 {% endif %}
 
+{% if multisig %}
+#[derive(Serialize, SchemaType)]
+pub struct UpdateMultisigParams {
+    /// The accounts approving operations.
+    pub signers:   Vec<AccountAddress>,
+    /// The number of approvals an operation needs. Zero disables multisig.
+    pub threshold: u32,
+}
+
+/// A call of a privileged entrypoint of this contract, which is executed by
+/// the contract itself once enough signers approved it.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq, Eq)]
+pub struct MultisigOperation {
+    pub entrypoint: OwnedEntrypointName,
+    pub parameter:  OwnedParameter,
+    /// The signers that approved the operation.
+    pub approvals:  Vec<AccountAddress>,
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct ProposeOperationParams {
+    pub entrypoint: OwnedEntrypointName,
+    pub parameter:  OwnedParameter,
+}
+
+/// The message a signer signs to approve an operation off-chain.
+#[derive(Serialize, SchemaType)]
+pub struct ApprovalMessage {
+    /// The contract_address that the signature is intended for.
+    pub contract_address: ContractAddress,
+    /// The operation that is approved.
+    pub operation_id:     u64,
+    /// A timestamp to make signatures expire.
+    pub timestamp:        Timestamp,
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct SignedApproval {
+    pub signature: AccountSignatures,
+    pub signer:    AccountAddress,
+    pub message:   ApprovalMessage,
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct ApproveOperationSignedParams {
+    #[concordium(size_length = 2)]
+    pub approvals: Vec<SignedApproval>,
+}
+{% endif %}
+
 {% if pausable %}
+/// Legacy parameter of the `setPaused` entrypoint. Pauses or unpauses every
+/// operation at once.
 #[derive(Serialize, SchemaType)]
 #[repr(transparent)]
 pub struct SetPausedParams {
     pub paused: bool,
 }
+
+/// The operations that can be paused independently of each other.
+#[derive(Serialize, SchemaType, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PausedOperations {
+    {% if mintable %}
+    pub mint:            bool,
+    {% endif %}
+    {% if burnable %}
+    pub burn:            bool,
+    {% endif %}
+    pub transfer:        bool,
+    pub update_operator: bool,
+    {% if wrapped_ccd %}
+    pub wrap:            bool,
+    pub unwrap:          bool,
+    {% endif %}
+}
+
+impl PausedOperations {
+    /// Pause or unpause every operation.
+    pub fn uniform(paused: bool) -> Self {
+        PausedOperations {
+            {% if mintable %}
+            mint: paused,
+            {% endif %}
+            {% if burnable %}
+            burn: paused,
+            {% endif %}
+            transfer: paused,
+            update_operator: paused,
+            {% if wrapped_ccd %}
+            wrap: paused,
+            unwrap: paused,
+            {% endif %}
+        }
+    }
+}
+
+{% if updates %}
+impl PausedOperations {
+    /// Read the paused operations of a variant with the given features. The
+    /// fields are read in the order they are written.
+    fn deserial_with_features<R: Read>(
+        {% if mintable or burnable or wrapped_ccd %}features{% else %}_features{% endif %}: u32,
+        source: &mut R,
+    ) -> ParseResult<Self> {
+        Ok(PausedOperations {
+            {% if mintable %}
+            mint: if features & FEATURE_MINTABLE != 0 { source.get()? } else { false },
+            {% endif %}
+            {% if burnable %}
+            burn: if features & FEATURE_BURNABLE != 0 { source.get()? } else { false },
+            {% endif %}
+            transfer: source.get()?,
+            update_operator: source.get()?,
+            {% if wrapped_ccd %}
+            wrap: if features & FEATURE_WRAPPED_CCD != 0 { source.get()? } else { false },
+            unwrap: if features & FEATURE_WRAPPED_CCD != 0 { source.get()? } else { false },
+            {% endif %}
+        })
+    }
+}
+{% endif %}
+
+/// The parameter of the `setPaused` entrypoint. A serialized `SetPausedParams`
+/// is still accepted and is applied to every operation.
+#[derive(Serialize, SchemaType)]
+pub struct PauseParams {
+    /// The new pause configuration of the operations.
+    pub operations: PausedOperations,
+    /// Tokens to pause (`true`) or unpause (`false`) individually.
+    #[concordium(size_length = 2)]
+    pub tokens:     Vec<(ContractTokenId, bool)>,
+}
 {% endif %}
 
 {% if updates %}
@@ -267,6 +1126,21 @@ pub struct UpgradeParams {
     pub module:  ModuleReference,
     pub migrate: Option<(OwnedEntrypointName, OwnedParameter)>,
 }
+
+#[derive(Serialize, SchemaType)]
+pub struct UpdateUpgradeAllowlistParams {
+    /// Modules to add to (`true`) or remove from (`false`) the allowlist.
+    #[concordium(size_length = 2)]
+    pub updates: Vec<(ModuleReference, bool)>,
+}
+
+/// An upgrade waiting for its timelock to pass.
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq, Eq)]
+pub struct UpgradeProposal {
+    pub migrate:          Option<(OwnedEntrypointName, OwnedParameter)>,
+    /// The upgrade cannot be executed before this time.
+    pub executable_after: Timestamp,
+}
 {% endif %}
 
 {% if roles %}
@@ -274,6 +1148,8 @@ pub struct UpgradeParams {
 pub struct GrantRoleParams {
     pub address: Address,
     pub role:    Roles,
+    /// The role is held until this time. `None` grants it until revoked.
+    pub expiry:  Option<Timestamp>,
 }
 
 #[derive(Serialize, SchemaType)]
@@ -282,11 +1158,104 @@ pub struct RevokeRoleParams {
     pub role:    Roles,
 }
 
+#[derive(Serialize, SchemaType)]
+pub struct HasRoleQueryParams {
+    pub address: Address,
+    pub role:    Roles,
+}
+
+/// Parameter of the `roleMembers` entrypoint, returning at most `limit`
+/// members after skipping the first `skip`.
+#[derive(Serialize, SchemaType)]
+pub struct RoleMembersQueryParams {
+    pub role:  Roles,
+    pub skip:  u32,
+    pub limit: u32,
+}
+
+{% if mintable %}
+/// The limits of a minter. Amounts are what is left to mint.
+#[derive(Serial, DeserialWithState, Deletable)]
+#[concordium(state_parameter = "S")]
+struct MinterQuota<S = StateApi> {
+    /// The amount left over all tokens, if limited.
+    total:           Option<ContractTokenAmount>,
+    /// The amount left of the tokens limited individually.
+    per_token:       StateMap<ContractTokenId, ContractTokenAmount, S>,
+    /// Inclusive ranges of the token IDs the minter may mint. Empty allows
+    /// any token ID.
+    token_id_ranges: Vec<(ContractTokenId, ContractTokenId)>,
+}
+
+#[derive(Serialize, SchemaType, Clone, Debug, PartialEq, Eq)]
+pub struct MinterQuotaParams {
+    /// The amount the minter may mint over all tokens. `None` is unlimited.
+    pub total:           Option<ContractTokenAmount>,
+    /// The amount the minter may mint of individual tokens.
+    #[concordium(size_length = 2)]
+    pub per_token:       Vec<(ContractTokenId, ContractTokenAmount)>,
+    /// Inclusive ranges of the token IDs the minter may mint. Empty allows
+    /// any token ID.
+    #[concordium(size_length = 2)]
+    pub token_id_ranges: Vec<(ContractTokenId, ContractTokenId)>,
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct SetMinterQuotaParams {
+    pub minter: Address,
+    /// The new limits of the minter, replacing the remaining quota. `None`
+    /// removes all limits.
+    pub quota:  Option<MinterQuotaParams>,
+}
+{% endif %}
+
 #[derive(Serial, DeserialWithState, Deletable)]
 #[concordium(state_parameter = "S")]
 struct AddressRoleState<S> {
-    roles: StateSet<Roles, S>,
+    /// The roles with the time they expire at, if any.
+    roles: StateMap<Roles, Option<Timestamp>, S>,
+}
+
+{% if updates %}
+/// Roles in the layout of a variant with different features, where each role
+/// is serialized as its index in the `Roles` of that variant.
+#[derive(Serial, DeserialWithState, Deletable)]
+#[concordium(state_parameter = "S")]
+struct LegacyAddressRoleState<S = StateApi> {
+    roles: StateMap<u8, Option<Timestamp>, S>,
+}
+
+{% if migrate_v3 %}
+/// Roles in the layout of the V3 variant with the same binary code, which had
+/// no expiry.
+#[derive(Serial, DeserialWithState, Deletable)]
+#[concordium(state_parameter = "S")]
+struct V3AddressRoleState<S = StateApi> {
+    roles: StateSet<u8, S>,
+}
+{% endif %}
+
+/// The roles of a variant with the given features, in the order of its
+/// `Roles`.
+fn roles_of_features(features: u32) -> Vec<Roles> {
+    let mut roles = vec![Roles::ADMIN];
+    if features & FEATURE_UPDATES != 0 {
+        roles.push(Roles::UPGRADER);
+    }
+    {% if pausable %}
+    if features & FEATURE_PAUSABLE != 0 {
+        roles.push(Roles::PAUSER);
+    }
+    {% endif %}
+    {% if mintable %}
+    if features & FEATURE_MINTABLE != 0 {
+        roles.push(Roles::MINTER);
+    }
+    {% endif %}
+    roles.push(Roles::ATTRIBUTEUPDATER);
+    roles
 }
+{% endif %}
 
 #[derive(Serialize, PartialEq, Eq, Reject, SchemaType, Clone, Copy, Debug)]
 pub enum Roles {
@@ -300,6 +1269,7 @@ pub enum Roles {
     {% if mintable %}
     MINTER,
     {% endif %}
+    ATTRIBUTEUPDATER,
 }
 {% endif %}
 
@@ -321,16 +1291,43 @@ impl AddressState {
 
 #[derive(Serial, DeserialWithState)]
 #[concordium(state_parameter = "S")]
-struct State<S = StateApi> {
+struct State<S: HasStateApi = StateApi> {
+    /// The version of the state layout.
+    version:            u8,
+    /// The features of the variant that wrote the state. Together with the
+    /// version it determines the layout of the remaining fields.
+    features:           u32,
+    /// The owner of the contract, set on init.
+    owner:              Address,
     /// The state of addresses.
     state:              StateMap<Address, AddressState<S>, S>,
     /// All of the token IDs.
     tokens:             StateMap<ContractTokenId, MetadataUrl, S>,
     max_supply:         StateMap<ContractTokenId, ContractTokenAmount, S>,
+    /// The circulating supply of each token, i.e. the amount held by all
+    /// holders.
     token_balance:      StateMap<ContractTokenId, ContractTokenAmount, S>,
     /// A map with contract addresses providing implementations of additional
     /// standards.
     implementors:       StateMap<StandardIdentifierOwned, Vec<ContractAddress>, S>,
+    /// The base URL of the metadata of tokens stored with an empty URL.
+    metadata_base:      Option<MetadataBase>,
+    /// The reveal that is prepared but not done yet.
+    pending_reveal:     Option<PendingReveal>,
+    /// Whether the metadata base is revealed and can no longer be changed.
+    revealed:           bool,
+    /// The on-chain attributes of tokens.
+    attributes:         StateMap<ContractTokenId, StateMap<String, String, S>, S>,
+    {% if mintable %}
+    /// Whether minting of all tokens is finalized.
+    minting_finalized:  bool,
+    /// The tokens that can no longer be minted.
+    finalized_tokens:   StateSet<ContractTokenId, S>,
+    /// The tokens whose max supply can no longer be updated.
+    max_supply_locked:  StateSet<ContractTokenId, S>,
+    /// The lowest token ID `mintNext` may allocate.
+    next_token_id:      u32,
+    {% endif %}
     {% if sponsored %}
     /// A registry to link an account to its next nonce. The nonce is used to
     /// prevent replay attacks of the signed message. The nonce is increased
@@ -339,13 +1336,70 @@ struct State<S = StateApi> {
     /// mapping keeps track of the next nonce that needs to be used by the
     /// account to generate a signature.
     nonces_registry:    StateMap<AccountAddress, u64, S>,
+    /// The addresses public keys are registered to by `registerPublicKey`.
+    public_keys:        StateMap<PublicKeyEd25519, Address, S>,
+    /// The next nonce of each public key signing permits.
+    public_key_nonces:  StateMap<PublicKeyEd25519, u64, S>,
     {% endif %}
     {% if pausable %}
-    paused:             bool,
+    /// The operations that are currently paused.
+    paused:             PausedOperations,
+    /// Token IDs that are paused individually for all operations.
+    paused_tokens:      StateSet<ContractTokenId, S>,
     {% endif %}
     {% if roles %}
     /// A map containing all roles granted to addresses.
     roles:              StateMap<Address, AddressRoleState<S>, S>,
+    /// The addresses holding each role.
+    role_members:       StateMap<Roles, StateSet<Address, S>, S>,
+    {% if mintable %}
+    /// The limits of minters. Minters without an entry are not limited.
+    minter_quotas:      StateMap<Address, MinterQuota<S>, S>,
+    {% endif %}
+    {% endif %}
+    {% if updates %}
+    /// The time an upgrade has to wait between being proposed and executed.
+    /// A zero delay allows calling `upgrade` directly.
+    upgrade_delay:      Duration,
+    /// The proposed upgrades by the module they upgrade to.
+    upgrade_proposals:  StateMap<ModuleReference, UpgradeProposal, S>,
+    /// The modules the contract may be upgraded to.
+    upgrade_allowlist:  StateSet<ModuleReference, S>,
+    {% endif %}
+    {% if multisig %}
+    /// The accounts approving multisig operations.
+    signers:            StateSet<AccountAddress, S>,
+    /// The number of approvals an operation needs. Zero disables multisig.
+    threshold:          u32,
+    /// The ID of the next proposed operation.
+    next_operation_id:  u64,
+    /// The operations waiting for approvals.
+    operations:         StateMap<u64, MultisigOperation, S>,
+    /// The entrypoint of the operation `approveOperation` is executing. Kept
+    /// in a box, so it is written without writing the root of the state.
+    executing_operation: StateBox<Option<OwnedEntrypointName>, S>,
+    {% endif %}
+    {% if receive_tokens %}
+    /// The amounts of tokens of other CIS-2 contracts received by
+    /// `onReceivingCIS2` and not recovered yet.
+    received_tokens:    StateMap<(ContractAddress, TokenIdVec), ReceivedTokenAmount, S>,
+    {% endif %}
+    {% if dividends %}
+    /// The dividends distributed to the holders of each token.
+    dividend_pools:     StateMap<ContractTokenId, DividendPool, S>,
+    /// The dividends of each holder and token. Holders without an account
+    /// have not changed their balance since dividends were first distributed.
+    dividend_accounts:  StateMap<(Address, ContractTokenId), DividendAccount, S>,
+    {% endif %}
+    {% if snapshots %}
+    /// The ID of the last snapshot. Zero before the first snapshot.
+    snapshot_id:        u64,
+    /// The balances at snapshots by holder, token and snapshot ID, recorded on
+    /// their first change after the snapshot.
+    balance_snapshots:  StateMap<(Address, ContractTokenId, u64), ContractTokenAmount, S>,
+    /// The supplies at snapshots by token and snapshot ID, recorded on their
+    /// first change after the snapshot.
+    supply_snapshots:   StateMap<(ContractTokenId, u64), ContractTokenAmount, S>,
     {% endif %}
 }
 
@@ -365,7 +1419,7 @@ pub enum CustomContractError {
     ContractOnly, // -5
     /// Failed to invoke a contract.
     InvokeContractError, // -6
-    {% if sponsored %}
+    {% if sponsored or multisig %}
     /// Failed to verify signature because signer account does not exist on
     /// chain.
     MissingAccount, // -7
@@ -406,10 +1460,104 @@ pub enum CustomContractError {
     {% endif %}
     /// Max supply reached
     MaxSupplyReached, // -22
-    {% if burnable %}
+    {% if burnable or wrapped_ccd %}
     /// No balance to burn
     NoBalanceToBurn, // -23
     {% endif %}
+    {% if pausable %}
+    /// The token is paused.
+    TokenPaused, // -24
+    {% endif %}
+    {% if updates %}
+    /// Upgrade failed because it has to be proposed and wait for the timelock.
+    UpgradeTimelocked, // -25
+    /// No upgrade to the module was proposed.
+    UpgradeNotProposed, // -26
+    /// An upgrade to the module was proposed already.
+    UpgradeAlreadyProposed, // -27
+    /// The upgrade delay can only be increased.
+    InvalidUpgradeDelay, // -28
+    /// Migration failed because the state was written by an unknown layout.
+    UnsupportedStateVersion, // -29
+    /// Migration failed because the old variant has features this one lacks.
+    UnsupportedFeatures, // -30
+    /// Upgrade failed because the module is not on the allowlist.
+    UpgradeModuleNotAllowed, // -31
+    {% endif %}
+    {% if multisig %}
+    /// The sender is not a multisig signer.
+    NotASigner, // -32
+    /// The multisig operation does not exist.
+    UnknownOperation, // -33
+    /// The threshold is zero or larger than the number of signers.
+    InvalidThreshold, // -34
+    /// Multisig is not enabled.
+    MultisigDisabled, // -35
+    {% endif %}
+    {% if roles %}
+    /// Failed to revoke the ADMIN role of the last admin.
+    LastAdmin, // -36
+    /// Failed to grant a role with an expiry in the past.
+    InvalidRoleExpiry, // -37
+    {% endif %}
+    {% if roles and mintable %}
+    /// Minting exceeds the quota of the minter.
+    MinterQuotaExceeded, // -38
+    /// The minter is not allowed to mint the token ID.
+    TokenIdNotAllowed, // -39
+    {% endif %}
+    {% if mintable %}
+    /// Minting of the token is finalized.
+    MintingFinalized, // -40
+    /// The max supply is below the circulating supply of the token.
+    MaxSupplyBelowSupply, // -41
+    /// The max supply of the token is locked.
+    MaxSupplyLocked, // -42
+    /// All token IDs are in use.
+    TokenIdsExhausted, // -43
+    {% endif %}
+    /// The token has no metadata URL and no base URL is set.
+    MissingMetadataBase, // -44
+    /// A reveal is already prepared.
+    RevealAlreadyPrepared, // -45
+    /// No reveal is prepared.
+    NoPendingReveal, // -46
+    /// The revealed metadata base does not match the commitment.
+    RevealCommitmentMismatch, // -47
+    {% if sponsored and updates %}
+    /// Upgrades with a migration can not be executed with `permit`.
+    PermitMigrationUnsupported, // -48
+    {% endif %}
+    {% if sponsored %}
+    /// The public key is not registered.
+    PublicKeyNotRegistered, // -49
+    /// The public key is registered to another address.
+    PublicKeyAlreadyRegistered, // -50
+    /// The permit message was signed for another domain.
+    WrongDomain, // -51
+    {% endif %}
+    {% if wrapped_ccd %}
+    /// The wrapped CCD token is only minted and burned by `wrap` and `unwrap`.
+    WrappedTokenReserved, // -52
+    {% endif %}
+    {% if wrapped_ccd or dividends %}
+    /// Failed to transfer CCD to an account.
+    InvokeTransferError, // -53
+    {% endif %}
+    {% if dividends %}
+    /// Dividends can not be distributed while nobody holds the token.
+    NoDividendHolders, // -54
+    /// The holder has no dividends to claim.
+    NothingToClaim, // -55
+    {% endif %}
+    {% if snapshots %}
+    /// The snapshot has not been taken.
+    UnknownSnapshot, // -56
+    {% endif %}
+    /// The metadata is revealed and can no longer be changed.
+    MetadataRevealed, // -57
+    /// The contract itself can only be called as a receiver at `wrap`.
+    InvalidReceiver, // -58
 }
 
 pub type ContractError = Cis2Error<CustomContractError>;
@@ -429,7 +1577,7 @@ impl From<UpgradeError> for CustomContractError {
 }
 {% endif %}
 
-{% if sponsored %}
+{% if sponsored or multisig %}
 impl From<CheckAccountSignatureError> for CustomContractError {
     fn from(e: CheckAccountSignatureError) -> Self {
         match e {
@@ -458,21 +1606,65 @@ impl From<CustomContractError> for ContractError {
 }
 
 impl State {
-    fn empty(state_builder: &mut StateBuilder) -> Self {
+    fn empty(owner: Address, state_builder: &mut StateBuilder) -> Self {
         State {
+            version: STATE_VERSION,
+            features: FEATURES,
+            owner,
             state: state_builder.new_map(),
             tokens: state_builder.new_map(),
             max_supply: state_builder.new_map(),
             token_balance: state_builder.new_map(),
             implementors: state_builder.new_map(),
+            metadata_base: None,
+            pending_reveal: None,
+            revealed: false,
+            attributes: state_builder.new_map(),
+            {% if mintable %}
+            minting_finalized: false,
+            finalized_tokens: state_builder.new_set(),
+            max_supply_locked: state_builder.new_set(),
+            next_token_id: 0,
+            {% endif %}
             {% if sponsored %}
             nonces_registry: state_builder.new_map(),
+            public_keys: state_builder.new_map(),
+            public_key_nonces: state_builder.new_map(),
             {% endif %}
             {% if pausable %}
-            paused: false,
+            paused: PausedOperations::default(),
+            paused_tokens: state_builder.new_set(),
             {% endif %}
             {% if roles %}
             roles: state_builder.new_map(),
+            role_members: state_builder.new_map(),
+            {% if mintable %}
+            minter_quotas: state_builder.new_map(),
+            {% endif %}
+            {% endif %}
+            {% if updates %}
+            upgrade_delay: Duration::from_millis(0),
+            upgrade_proposals: state_builder.new_map(),
+            upgrade_allowlist: state_builder.new_set(),
+            {% endif %}
+            {% if multisig %}
+            signers: state_builder.new_set(),
+            threshold: 0,
+            next_operation_id: 0,
+            operations: state_builder.new_map(),
+            executing_operation: state_builder.new_box(None),
+            {% endif %}
+            {% if receive_tokens %}
+            received_tokens: state_builder.new_map(),
+            {% endif %}
+            {% if dividends %}
+            dividend_pools: state_builder.new_map(),
+            dividend_accounts: state_builder.new_map(),
+            {% endif %}
+            {% if snapshots %}
+            snapshot_id: 0,
+            balance_snapshots: state_builder.new_map(),
+            supply_snapshots: state_builder.new_map(),
             {% endif %}
         }
     }
@@ -489,6 +1681,13 @@ impl State {
         if token_metadata.is_none() {
             let _ = self.tokens.insert(*token_id, metadata_url.to_owned());
         }
+        {% if dividends %}
+        self.settle_dividends(token_id, owner);
+        {% endif %}
+        {% if snapshots %}
+        self.snapshot_balance(token_id, owner);
+        self.snapshot_supply(token_id);
+        {% endif %}
         let mut owner_state = self
             .state
             .entry(*owner)
@@ -500,7 +1699,34 @@ impl State {
         *circulating_supply += amount;
     }
 
-    {% if burnable %}
+    {% if mintable %}
+    /// Allocate the next token ID that is not in use yet.
+    fn next_free_token_id(&mut self) -> ContractResult<ContractTokenId> {
+        loop {
+            let token_id = u8::try_from(self.next_token_id)
+                .map(TokenIdU8)
+                .map_err(|_| CustomContractError::TokenIdsExhausted)?;
+            self.next_token_id += 1;
+            if !self.contains_token(&token_id) {
+                return Ok(token_id);
+            }
+        }
+    }
+
+    /// Fail if minting of the token is finalized.
+    fn ensure_mintable(&self, token_id: &ContractTokenId) -> ContractResult<()> {
+        {% if wrapped_ccd %}
+        ensure!(*token_id != WRAPPED_TOKEN_ID, CustomContractError::WrappedTokenReserved.into());
+        {% endif %}
+        ensure!(
+            !self.minting_finalized && !self.finalized_tokens.contains(token_id),
+            CustomContractError::MintingFinalized.into()
+        );
+        Ok(())
+    }
+    {% endif %}
+
+    {% if burnable or wrapped_ccd %}
     fn burn(
         &mut self,
         token_id: &ContractTokenId,
@@ -511,6 +1737,13 @@ impl State {
         if amount == 0u64.into() {
             return Ok(amount);
         }
+        {% if dividends %}
+        self.settle_dividends(token_id, owner);
+        {% endif %}
+        {% if snapshots %}
+        self.snapshot_balance(token_id, owner);
+        self.snapshot_supply(token_id);
+        {% endif %}
     
         match self.state.get_mut(owner) {
             Some(mut address_state) => match address_state.balances.get_mut(token_id) {
@@ -521,6 +1754,9 @@ impl State {
                     );
     
                     *b -= amount;
+                    if let Some(mut circulating_supply) = self.token_balance.get_mut(token_id) {
+                        *circulating_supply -= amount;
+                    }
                     Ok(*b)
                 }
                 None => Err(Cis2Error::Custom(CustomContractError::NoBalanceToBurn)),
@@ -536,20 +1772,79 @@ impl State {
         self.tokens.get(token_id).map(|x| x.to_owned()).is_some()
     }
 
-    fn balance(
-        &self,
-        token_id: &ContractTokenId,
-        address: &Address,
-    ) -> ContractResult<ContractTokenAmount> {
-        ensure!(self.contains_token(token_id), ContractError::InvalidTokenId);
-        let balance = self.state.get(address).map_or(0.into(), |address_state| {
-            address_state.balances.get(token_id).map_or(0.into(), |x| *x)
+    {% if wrapped_ccd %}
+    /// Register the wrapped CCD token for a state migrated from a variant
+    /// without it. Its metadata URL comes from the metadata base.
+    fn register_wrapped_token(&mut self) -> ContractResult<()> {
+        ensure!(!self.contains_token(&WRAPPED_TOKEN_ID), CustomContractError::WrappedTokenReserved.into());
+        let _ = self.tokens.insert(WRAPPED_TOKEN_ID, MetadataUrl {
+            url:  String::new(),
+            hash: None,
         });
-        Ok(balance)
+        Ok(())
     }
 
-    #[inline(always)]
-    fn set_max_supply(&mut self, token_id: &ContractTokenId, max_supply: ContractTokenAmount) {
+    {% endif %}
+    /// The metadata URL of a token. Tokens stored with an empty URL use the
+    /// placeholder until revealed and the base URL after.
+    fn token_metadata_url(&self, token_id: &ContractTokenId) -> ContractResult<MetadataUrl> {
+        let metadata_url =
+            self.tokens.get(token_id).map(|x| x.to_owned()).ok_or(ContractError::InvalidTokenId)?;
+        if !metadata_url.url.is_empty() {
+            return Ok(metadata_url);
+        }
+        if let Some(reveal) = &self.pending_reveal {
+            return Ok(reveal.placeholder.clone());
+        }
+        let base = self.metadata_base.as_ref().ok_or(CustomContractError::MissingMetadataBase)?;
+        Ok(MetadataUrl {
+            url:  format!("{}{:02x}{}", base.url, token_id.0, base.suffix),
+            hash: metadata_url.hash,
+        })
+    }
+
+    /// Set or remove attributes of a token.
+    fn set_attributes(
+        &mut self,
+        token_id: &ContractTokenId,
+        attributes: &[(String, Option<String>)],
+        state_builder: &mut StateBuilder,
+    ) {
+        let mut token_attributes =
+            self.attributes.entry(*token_id).or_insert_with(|| state_builder.new_map());
+        for (key, value) in attributes {
+            match value {
+                Some(value) => {
+                    let _ = token_attributes.insert(key.clone(), value.clone());
+                }
+                None => {
+                    token_attributes.remove(key);
+                }
+            }
+        }
+    }
+
+    /// The attributes of a token.
+    fn token_attributes(&self, token_id: &ContractTokenId) -> Vec<(String, String)> {
+        self.attributes.get(token_id).map_or_else(Vec::new, |token_attributes| {
+            token_attributes.iter().map(|(key, value)| (key.clone(), value.clone())).collect()
+        })
+    }
+
+    fn balance(
+        &self,
+        token_id: &ContractTokenId,
+        address: &Address,
+    ) -> ContractResult<ContractTokenAmount> {
+        ensure!(self.contains_token(token_id), ContractError::InvalidTokenId);
+        let balance = self.state.get(address).map_or(0.into(), |address_state| {
+            address_state.balances.get(token_id).map_or(0.into(), |x| *x)
+        });
+        Ok(balance)
+    }
+
+    #[inline(always)]
+    fn set_max_supply(&mut self, token_id: &ContractTokenId, max_supply: ContractTokenAmount) {
         let _ = self.max_supply.insert(*token_id, max_supply);
     }
 
@@ -557,7 +1852,7 @@ impl State {
     #[inline(always)]
     fn get_token_supply(&self, token_id: &ContractTokenId) -> ContractResult<ContractTokenAmount> {
         ensure!(
-            self.contains_token(&token_id),
+            self.contains_token(token_id),
             ContractError::InvalidTokenId
         );
         let supply = self.max_supply.get(token_id).map_or(0.into(), |x| *x);
@@ -595,6 +1890,14 @@ impl State {
         if amount == 0.into() {
             return Ok(());
         }
+        {% if dividends %}
+        self.settle_dividends(token_id, from);
+        self.settle_dividends(token_id, to);
+        {% endif %}
+        {% if snapshots %}
+        self.snapshot_balance(token_id, from);
+        self.snapshot_balance(token_id, to);
+        {% endif %}
 
         {
             let mut from_address_state =
@@ -615,6 +1918,112 @@ impl State {
         Ok(())
     }
 
+    {% if dividends %}
+    /// The dividends of the holder that are not claimed yet, including those
+    /// accrued since they were last settled.
+    fn claimable_dividends(&self, token_id: &ContractTokenId, holder: &Address) -> Amount {
+        let per_unit = self.dividend_pools.get(token_id).map_or(0, |pool| pool.per_unit);
+        let (paid_per_unit, owed) = self
+            .dividend_accounts
+            .get(&(*holder, *token_id))
+            .map_or((0, Amount::zero()), |account| (account.paid_per_unit, account.owed));
+        let balance = self.state.get(holder).map_or(0, |address_state| {
+            address_state.balances.get(token_id).map_or(0, |x| x.0)
+        });
+        let accrued = u128::from(balance) * (per_unit - paid_per_unit) / DIVIDEND_MAGNITUDE;
+        owed + Amount::from_micro_ccd(accrued as u64)
+    }
+
+    /// Settle the dividends accrued by the balance of the holder. Has to be
+    /// called before every change of the balance.
+    fn settle_dividends(&mut self, token_id: &ContractTokenId, holder: &Address) {
+        let per_unit = self.dividend_pools.get(token_id).map_or(0, |pool| pool.per_unit);
+        if per_unit == 0 {
+            return;
+        }
+        let owed = self.claimable_dividends(token_id, holder);
+        let _ = self.dividend_accounts.insert((*holder, *token_id), DividendAccount {
+            paid_per_unit: per_unit,
+            owed,
+        });
+    }
+    {% endif %}
+
+    {% if snapshots %}
+    /// Record the balance of the holder at the last snapshot, unless it was
+    /// recorded already. Has to be called before every change of the balance.
+    fn snapshot_balance(&mut self, token_id: &ContractTokenId, holder: &Address) {
+        if self.snapshot_id == 0 {
+            return;
+        }
+        let key = (*holder, *token_id, self.snapshot_id);
+        if self.balance_snapshots.get(&key).is_some() {
+            return;
+        }
+        let balance = self.state.get(holder).map_or(0.into(), |address_state| {
+            address_state.balances.get(token_id).map_or(0.into(), |x| *x)
+        });
+        let _ = self.balance_snapshots.insert(key, balance);
+    }
+
+    /// Record the supply of the token at the last snapshot, unless it was
+    /// recorded already. Has to be called before every change of the supply.
+    fn snapshot_supply(&mut self, token_id: &ContractTokenId) {
+        if self.snapshot_id == 0 {
+            return;
+        }
+        let key = (*token_id, self.snapshot_id);
+        if self.supply_snapshots.get(&key).is_some() {
+            return;
+        }
+        let supply = self.token_balance.get(token_id).map_or(0.into(), |x| *x);
+        let _ = self.supply_snapshots.insert(key, supply);
+    }
+
+    /// The balance of the holder at the snapshot, which is the balance
+    /// recorded for the first snapshot since then. The balance has not changed
+    /// since if none was recorded.
+    fn balance_at(
+        &self,
+        token_id: &ContractTokenId,
+        holder: &Address,
+        snapshot_id: u64,
+    ) -> ContractResult<ContractTokenAmount> {
+        self.ensure_snapshot(snapshot_id)?;
+        for id in snapshot_id..=self.snapshot_id {
+            if let Some(balance) = self.balance_snapshots.get(&(*holder, *token_id, id)) {
+                return Ok(*balance);
+            }
+        }
+        self.balance(token_id, holder)
+    }
+
+    /// The supply of the token at the snapshot, found like the balance in
+    /// `balance_at`.
+    fn supply_at(
+        &self,
+        token_id: &ContractTokenId,
+        snapshot_id: u64,
+    ) -> ContractResult<ContractTokenAmount> {
+        self.ensure_snapshot(snapshot_id)?;
+        ensure!(self.contains_token(token_id), ContractError::InvalidTokenId);
+        for id in snapshot_id..=self.snapshot_id {
+            if let Some(supply) = self.supply_snapshots.get(&(*token_id, id)) {
+                return Ok(*supply);
+            }
+        }
+        Ok(self.token_balance.get(token_id).map_or(0.into(), |x| *x))
+    }
+
+    fn ensure_snapshot(&self, snapshot_id: u64) -> ContractResult<()> {
+        ensure!(
+            snapshot_id > 0 && snapshot_id <= self.snapshot_id,
+            CustomContractError::UnknownSnapshot.into()
+        );
+        Ok(())
+    }
+    {% endif %}
+
     fn add_operator(
         &mut self,
         owner: &Address,
@@ -650,14 +2059,48 @@ impl State {
         let _ = self.implementors.insert(std_id, implementors);
     }
 
+    {% if pausable %}
+    /// Check that neither the operation nor the token is paused.
+    fn ensure_not_paused(
+        &self,
+        operation_paused: bool,
+        token_id: Option<&ContractTokenId>,
+    ) -> ContractResult<()> {
+        ensure!(!operation_paused, CustomContractError::Paused.into());
+        if let Some(token_id) = token_id {
+            ensure!(
+                !self.paused_tokens.contains(token_id),
+                CustomContractError::TokenPaused.into()
+            );
+        }
+        Ok(())
+    }
+    {% endif %}
+
+    {% if multisig %}
+    /// Whether privileged actions have to go through multisig operations.
+    fn multisig_enabled(&self) -> bool { self.threshold > 0 }
+    {% endif %}
+
     {% if roles %}
-    fn grant_role(&mut self, account: &Address, role: Roles, state_builder: &mut StateBuilder) {
+    fn grant_role(
+        &mut self,
+        account: &Address,
+        role: Roles,
+        expiry: Option<Timestamp>,
+        state_builder: &mut StateBuilder,
+    ) {
         self.roles.entry(*account).or_insert_with(|| AddressRoleState {
-            roles: state_builder.new_set(),
+            roles: state_builder.new_map(),
         });
 
         self.roles.entry(*account).and_modify(|entry| {
-            entry.roles.insert(role);
+            let _ = entry.roles.insert(role, expiry);
+        });
+
+        self.role_members.entry(role).or_insert_with(|| state_builder.new_set());
+        self.role_members.entry(role).and_modify(|members| {
+            members.insert(*account);
         });
     }
 
@@ -665,13 +2108,81 @@ impl State {
         self.roles.entry(*account).and_modify(|entry| {
             entry.roles.remove(&role);
         });
+
+        self.role_members.entry(role).and_modify(|members| {
+            members.remove(account);
+        });
     }
 
-    fn has_role(&self, account: &Address, role: Roles) -> bool {
-        return match self.roles.get(account) {
-            None => false,
-            Some(roles) => roles.roles.contains(&role),
+    /// Revoke a role, unless it is the ADMIN role of the last admin. Expired
+    /// grants can be revoked as well.
+    fn ensure_revoke_role(&mut self, account: &Address, role: Roles) -> ContractResult<()> {
+        ensure!(
+            self.role_grant(account, role).is_some(),
+            CustomContractError::RoleWasNotGranted.into()
+        );
+        if role == Roles::ADMIN {
+            // Another admin has to remain, who does not lose the role over time.
+            let other_admins = self.role_members.get(&Roles::ADMIN).map_or(0, |members| {
+                members
+                    .iter()
+                    .filter(|admin| {
+                        **admin != *account
+                            && self.role_grant(admin, Roles::ADMIN) == Some(None)
+                    })
+                    .count()
+            });
+            ensure!(other_admins > 0, CustomContractError::LastAdmin.into());
+        }
+        self.revoke_role(account, role);
+        Ok(())
+    }
+
+    /// The grant of a role to an account with its expiry, if the role was
+    /// granted.
+    fn role_grant(&self, account: &Address, role: Roles) -> Option<Option<Timestamp>> {
+        self.roles.get(account).and_then(|roles| roles.roles.get(&role).map(|expiry| *expiry))
+    }
+
+    {% if mintable %}
+    /// Deduct minted tokens from the quota of a minter, failing if they
+    /// exceed it.
+    fn use_minter_quota(
+        &mut self,
+        minter: &Address,
+        tokens: &collections::BTreeMap<ContractTokenId, (MetadataUrl, TokenParams)>,
+    ) -> ContractResult<()> {
+        let mut quota = match self.minter_quotas.get_mut(minter) {
+            None => return Ok(()),
+            Some(quota) => quota,
         };
+        for (token_id, (_, token_params)) in tokens {
+            let amount = token_params.amount;
+            ensure!(
+                quota.token_id_ranges.is_empty()
+                    || quota.token_id_ranges.iter().any(|(from, to)| from <= token_id && token_id <= to),
+                CustomContractError::TokenIdNotAllowed.into()
+            );
+            if let Some(total) = quota.total.as_mut() {
+                ensure!(*total >= amount, CustomContractError::MinterQuotaExceeded.into());
+                *total -= amount;
+            }
+            if let Some(mut remaining) = quota.per_token.get_mut(token_id) {
+                ensure!(*remaining >= amount, CustomContractError::MinterQuotaExceeded.into());
+                *remaining -= amount;
+            }
+        }
+        Ok(())
+    }
+    {% endif %}
+
+    /// Whether the account holds the role at the given time.
+    fn has_role(&self, account: &Address, role: Roles, now: Timestamp) -> bool {
+        match self.role_grant(account, role) {
+            None => false,
+            Some(None) => true,
+            Some(Some(expiry)) => now < expiry,
+        }
     }
     {% endif %}
 }
@@ -679,7 +2190,7 @@ impl State {
 // Contract functions
 
 #[init(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     parameter = "InitParams",
     event = "Cis2Event<ContractTokenId, ContractTokenAmount>",
     enable_logger
@@ -689,42 +2200,84 @@ fn contract_init(
     state_builder: &mut StateBuilder,
     logger: &mut impl HasLogger,
 ) -> InitResult<State> {
-    let params: InitParams = ctx.parameter_cursor().get()?;
+    let mut params = InitParams::parse(&mut ctx.parameter_cursor())?;
+
+    let owner = params.owner.unwrap_or(Address::Account(ctx.init_origin()));
 
-    let mut state = State::empty(state_builder);
+    let mut state = State::empty(owner, state_builder);
+    state.metadata_base = params.metadata_base;
 
-    let invoker = Address::Account(ctx.init_origin());
+    {% if wrapped_ccd %}
+    ensure!(
+        !params.premint_tokens.contains_key(&WRAPPED_TOKEN_ID),
+        CustomContractError::WrappedTokenReserved.into()
+    );
+    let _ = state.tokens.insert(WRAPPED_TOKEN_ID, params.wrapped_metadata);
+    logger.log(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(TokenMetadataEvent {
+        token_id:     WRAPPED_TOKEN_ID,
+        metadata_url: state.token_metadata_url(&WRAPPED_TOKEN_ID)?,
+    }))?;
+    {% endif %}
 
     {% if roles %}
-    state.grant_role(&invoker, Roles::ADMIN, state_builder);
+    state.grant_role(&owner, Roles::ADMIN, None, state_builder);
     logger.log(&Event::GrantRole(GrantRoleEvent {
-        address: invoker,
+        address: owner,
         role:    Roles::ADMIN,
+        expiry:  None,
     }))?;
+
+    for (address, role) in params.roles {
+        ensure!(
+            state.role_grant(&address, role).is_none(),
+            CustomContractError::RoleWasAlreadyGranted.into()
+        );
+        state.grant_role(&address, role, None, state_builder);
+        logger.log(&Event::GrantRole(GrantRoleEvent {
+            address,
+            role,
+            expiry: None,
+        }))?;
+    }
     {% endif %}
 
+    // Every recipient has to receive a preminted token.
+    ensure!(
+        params.premint_recipients.keys().all(|token_id| params.premint_tokens.contains_key(token_id)),
+        ContractError::InvalidTokenId.into()
+    );
+
     // Preminting of tokens
     for (token_id, token_info) in params.premint_tokens {
         state.set_max_supply(&token_id, token_info.1.max_supply);
 
-        state.mint(
-            &token_id,
-            &token_info.0,
-            token_info.1.amount,
-            &invoker,
-            state_builder,
-        );
+        let recipients = params.premint_recipients.remove(&token_id).unwrap_or_default();
+        let mut remaining = token_info.1.amount;
+        for (_, amount) in recipients.iter() {
+            ensure!(*amount <= remaining, ContractError::InsufficientFunds.into());
+            remaining -= *amount;
+        }
 
-        logger.log(&Cis2Event::Mint(MintEvent {
-            token_id,
-            amount: token_info.1.amount,
-            owner: invoker,
-        }))?;
+        // The owner receives the rest, unless recipients took all of it.
+        let owner_amount = if recipients.is_empty() || remaining > 0u64.into() {
+            Some((owner, remaining))
+        } else {
+            None
+        };
+        for (recipient, amount) in recipients.into_iter().chain(owner_amount) {
+            state.mint(&token_id, &token_info.0, amount, &recipient, state_builder);
+
+            logger.log(&Cis2Event::Mint(MintEvent {
+                token_id,
+                amount,
+                owner: recipient,
+            }))?;
+        }
 
         logger.log(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(
             TokenMetadataEvent {
                 token_id,
-                metadata_url: token_info.0,
+                metadata_url: state.token_metadata_url(&token_id)?,
             },
         ))?;
     }
@@ -738,25 +2291,44 @@ pub struct ViewAddressState {
     pub operators: Vec<Address>,
 }
 
+{% if roles %}
+/// The roles of an address and when they expire.
+pub type ViewAddressRoles = Vec<(Roles, Option<Timestamp>)>;
+{% endif %}
+
 #[derive(Serialize, SchemaType, PartialEq, Eq)]
 pub struct ViewState {
+    pub owner:           Address,
     pub state:           Vec<(Address, ViewAddressState)>,
     pub tokens:          Vec<ContractTokenId>,
     {% if sponsored %}
     pub nonces_registry: Vec<(AccountAddress, u64)>,
     {% endif %}
     {% if roles %}
-    pub roles:           Vec<(Address, Vec<Roles>)>,
+    pub roles:           Vec<(Address, ViewAddressRoles)>,
     {% endif %}
     {% if pausable %}
-    pub paused:          bool,
+    pub paused:          PausedOperations,
+    pub paused_tokens:   Vec<ContractTokenId>,
+    {% endif %}
+    {% if updates %}
+    pub upgrade_allowlist: Vec<ModuleReference>,
+    {% endif %}
+    {% if mintable %}
+    pub minting_finalized: bool,
+    pub finalized_tokens: Vec<ContractTokenId>,
+    pub max_supply_locked: Vec<ContractTokenId>,
     {% endif %}
+    pub metadata_base:   Option<MetadataBase>,
+    pub pending_reveal:  Option<PendingReveal>,
+    pub revealed:        bool,
+    pub attributes:      Vec<(ContractTokenId, Vec<(String, String)>)>,
     pub implementors:    Vec<(StandardIdentifierOwned, Vec<ContractAddress>)>,
 }
 
 /// View function for testing. This reports on the entire state of the contract
 /// for testing purposes.
-#[receive(contract = "mint_wizard_{{ code }}_V{{ version }}", name = "view", return_value = "ViewState")]
+#[receive(contract = "{{ contract_name }}", name = "view", return_value = "ViewState")]
 fn contract_view(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<ViewState> {
     let state = host.state();
 
@@ -784,13 +2356,13 @@ fn contract_view(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<Vie
     let nonces_registry = state.nonces_registry.iter().map(|(a, b)| (*a, *b)).collect();
     {% endif %}
     {% if roles %}
-    let roles: Vec<(Address, Vec<Roles>)> = state
+    let roles: Vec<(Address, ViewAddressRoles)> = state
         .roles
         .iter()
         .map(|(key, value)| {
             let mut roles_vec = Vec::new();
-            for role in value.roles.iter() {
-                roles_vec.push(*role);
+            for (role, expiry) in value.roles.iter() {
+                roles_vec.push((*role, *expiry));
             }
             (*key, roles_vec)
         })
@@ -811,6 +2383,7 @@ fn contract_view(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<Vie
         .collect();
 
     Ok(ViewState {
+        owner: state.owner,
         state: contract_state,
         tokens,
         {% if sponsored %}
@@ -821,7 +2394,28 @@ fn contract_view(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<Vie
         {% endif %}
         implementors,
         {% if pausable %}
-        paused: host.state().paused,
+        paused: state.paused,
+        paused_tokens: state.paused_tokens.iter().map(|a| *a).collect(),
+        {% endif %}
+        {% if updates %}
+        upgrade_allowlist: state.upgrade_allowlist.iter().map(|a| *a).collect(),
+        {% endif %}
+        metadata_base: state.metadata_base.clone(),
+        pending_reveal: state.pending_reveal.clone(),
+        revealed: state.revealed,
+        attributes: state
+            .attributes
+            .iter()
+            .map(|(token_id, token_attributes)| {
+                let token_attributes =
+                    token_attributes.iter().map(|(key, value)| (key.clone(), value.clone())).collect();
+                (*token_id, token_attributes)
+            })
+            .collect(),
+        {% if mintable %}
+        minting_finalized: state.minting_finalized,
+        finalized_tokens: state.finalized_tokens.iter().map(|a| *a).collect(),
+        max_supply_locked: state.max_supply_locked.iter().map(|a| *a).collect(),
         {% endif %}
     })
 }
@@ -834,11 +2428,17 @@ fn mint(
 ) -> ContractResult<()> {
 
     {% if pausable %}
-    ensure!(!host.state().paused, CustomContractError::Paused.into());
+    ensure!(!host.state().paused.mint, CustomContractError::Paused.into());
     {% endif %}
 
     let (state, builder) = host.state_and_builder();
     for (token_id, token_info) in params.tokens {
+        {% if pausable %}
+        state.ensure_not_paused(false, Some(&token_id))?;
+
+        {% endif %}
+        state.ensure_mintable(&token_id)?;
+
         if !state.contains_token(&token_id) {
             state.set_max_supply(&token_id, token_info.1.max_supply);
         } else {
@@ -851,7 +2451,7 @@ fn mint(
             );
 
             ensure!(
-                &token_info.1.amount <= &(max_supply - circulating_supply),
+                token_info.1.amount <= max_supply - circulating_supply,
                 ContractError::Custom(CustomContractError::MaxSupplyReached)
             );
         }
@@ -870,10 +2470,11 @@ fn mint(
             owner: params.owner,
         }))?;
 
+        // The metadata of a token that exists already is not replaced.
         logger.log(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(
             TokenMetadataEvent {
                 token_id,
-                metadata_url: token_info.0,
+                metadata_url: state.token_metadata_url(&token_id)?,
             },
         ))?;
     }
@@ -882,7 +2483,7 @@ fn mint(
 }
 
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "mint",
     parameter = "MintParams",
     error = "ContractError",
@@ -894,100 +2495,509 @@ fn contract_mint(
     host: &mut Host<State>,
     logger: &mut Logger,
 ) -> ContractResult<()> {
-    let owner = ctx.owner();
-    let sender = ctx.sender();
+    {% if roles %}let limited_minter = {% endif %}ensure_minter(ctx, host.state())?;
+
+    let params: MintParams = ctx.parameter_cursor().get()?;
 
     {% if roles %}
-    ensure!(
-        host.state().has_role(&sender, Roles::MINTER)
-        || sender.matches_account(&owner),
-        ContractError::Unauthorized
-    );
-    {% else %}
-    ensure!(sender.matches_account(&owner), ContractError::Unauthorized);
+    if limited_minter {
+        host.state_mut().use_minter_quota(&ctx.sender(), &params.tokens)?;
+    }
     {% endif %}
-    
-    let params: MintParams = ctx.parameter_cursor().get()?;
 
     mint(params, host, logger)?;
 
     Ok(())
 }
-{% endif %}
 
-{% if burnable %}
-fn burn(
-    params: BurnParams,
+/// Mint tokens with the next free token IDs. Returns the allocated IDs.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "mintNext",
+    parameter = "MintNextParams",
+    return_value = "Vec<ContractTokenId>",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_mint_next(
+    ctx: &ReceiveContext,
     host: &mut Host<State>,
-    logger: &mut impl HasLogger,
-) -> ContractResult<()> {
-    {% if pausable %}
-    ensure!(!host.state().paused, CustomContractError::Paused.into());
+    logger: &mut Logger,
+) -> ContractResult<Vec<ContractTokenId>> {
+    {% if roles %}let limited_minter = {% endif %}ensure_minter(ctx, host.state())?;
+
+    let params: MintNextParams = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    let mut token_ids = Vec::with_capacity(params.tokens.len());
+    let mut tokens = collections::BTreeMap::new();
+    for token_info in params.tokens {
+        let token_id = state.next_free_token_id()?;
+        token_ids.push(token_id);
+        tokens.insert(token_id, token_info);
+    }
+
+    {% if roles %}
+    if limited_minter {
+        state.use_minter_quota(&ctx.sender(), &tokens)?;
+    }
     {% endif %}
 
-    let (state, _builder) = host.state_and_builder();
+    mint(
+        MintParams {
+            owner: params.owner,
+            tokens,
+        },
+        host,
+        logger,
+    )?;
 
-    let token_id = params.token_id;
+    Ok(token_ids)
+}
 
-    let amount = params.amount;
+/// Check that the sender may mint. Returns whether the sender is a minter
+/// limited by its quota, rather than the owner.
+fn ensure_minter(ctx: &ReceiveContext, state: &State) -> ContractResult<bool> {
+    let owner = state.owner;
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, state)?;
+    {% endif %}
+    {% if roles %}
     ensure!(
-        state.contains_token(&token_id),
-        ContractError::InvalidTokenId
+        {% if multisig %}multisig_call || {% endif %}state.has_role(&sender, Roles::MINTER, ctx.metadata().slot_time())
+        || sender == owner,
+        ContractError::Unauthorized
     );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
 
-    let _: ContractTokenAmount = state.burn(&token_id, amount, &params.owner)?;
-
-    logger.log(&Cis2Event::Burn(BurnEvent {
-        token_id,
-        amount,
-        owner: params.owner,
-    }))?;
-
-    Ok(())
+    Ok({% if multisig %}!multisig_call && {% endif %}sender != owner)
 }
 
+/// Permanently disable minting of a token, or of all tokens if no token ID is
+/// given.
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
-    name = "burn",
-    parameter = "BurnParams",
+    contract = "{{ contract_name }}",
+    name = "finalizeMinting",
+    parameter = "Option<ContractTokenId>",
     error = "ContractError",
     enable_logger,
     mutable
 )]
-fn contract_burn(
+fn contract_finalize_minting(
     ctx: &ReceiveContext,
     host: &mut Host<State>,
-    logger: &mut Logger,
+    logger: &mut impl HasLogger,
 ) -> ContractResult<()> {
-    let owner = ctx.owner();
     let sender = ctx.sender();
 
-    ensure!(sender.matches_account(&owner), ContractError::Unauthorized);
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let token_id: Option<ContractTokenId> = ctx.parameter_cursor().get()?;
 
-    let params: BurnParams = ctx.parameter_cursor().get()?;
+    let state = host.state_mut();
+    match token_id {
+        None => {
+            ensure!(!state.minting_finalized, CustomContractError::MintingFinalized.into());
+            state.minting_finalized = true;
+        }
+        Some(token_id) => {
+            state.ensure_mintable(&token_id)?;
+            state.finalized_tokens.insert(token_id);
+        }
+    }
 
-    burn(params, host, logger)?;
+    logger.log(&Event::MintingFinalized(MintingFinalizedEvent {
+        token_id,
+    }))?;
 
     Ok(())
 }
-{% endif %}
-
-type TransferParameter = TransferParams<ContractTokenId, ContractTokenAmount>;
 
-fn transfer(
-    transfer: concordium_cis2::Transfer<ContractTokenId, ContractTokenAmount>,
+/// Raise or lower the max supply of a token, down to its circulating supply.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "updateMaxSupply",
+    parameter = "UpdateMaxSupplyParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_update_max_supply(
+    ctx: &ReceiveContext,
     host: &mut Host<State>,
     logger: &mut impl HasLogger,
 ) -> ContractResult<()> {
-    let to_address = transfer.to.address();
+    let sender = ctx.sender();
 
-    {% if pausable %}
-    ensure!(!host.state().paused, CustomContractError::Paused.into());
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
     {% endif %}
-
-    let (state, builder) = host.state_and_builder();
-
-    state.transfer(&transfer.token_id, transfer.amount, &transfer.from, &to_address, builder)?;
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let params: UpdateMaxSupplyParams = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    let circulating_supply = state.get_circulating_supply(&params.token_id)?;
+    ensure!(
+        !state.max_supply_locked.contains(&params.token_id),
+        CustomContractError::MaxSupplyLocked.into()
+    );
+    ensure!(
+        params.max_supply >= circulating_supply,
+        CustomContractError::MaxSupplyBelowSupply.into()
+    );
+    state.set_max_supply(&params.token_id, params.max_supply);
+
+    logger.log(&Event::MaxSupplyUpdated(MaxSupplyUpdatedEvent {
+        token_id:   params.token_id,
+        max_supply: params.max_supply,
+    }))?;
+
+    Ok(())
+}
+
+/// Make the max supply of a token immutable.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "lockMaxSupply",
+    parameter = "ContractTokenId",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_lock_max_supply(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let token_id: ContractTokenId = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    ensure!(state.contains_token(&token_id), ContractError::InvalidTokenId);
+    ensure!(state.max_supply_locked.insert(token_id), CustomContractError::MaxSupplyLocked.into());
+
+    logger.log(&Event::MaxSupplyLocked(MaxSupplyLockedEvent {
+        token_id,
+    }))?;
+
+    Ok(())
+}
+{% endif %}
+
+{% if burnable %}
+fn burn(
+    params: BurnParams,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let (state, _builder) = host.state_and_builder();
+
+    let token_id = params.token_id;
+
+    {% if pausable %}
+    state.ensure_not_paused(state.paused.burn, Some(&token_id))?;
+    {% endif %}
+
+    let amount = params.amount;
+    ensure!(
+        state.contains_token(&token_id),
+        ContractError::InvalidTokenId
+    );
+    {% if wrapped_ccd %}
+    ensure!(token_id != WRAPPED_TOKEN_ID, CustomContractError::WrappedTokenReserved.into());
+    {% endif %}
+
+    let _: ContractTokenAmount = state.burn(&token_id, amount, &params.owner)?;
+
+    logger.log(&Cis2Event::Burn(BurnEvent {
+        token_id,
+        amount,
+        owner: params.owner,
+    }))?;
+
+    Ok(())
+}
+
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "burn",
+    parameter = "BurnParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_burn(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut Logger,
+) -> ContractResult<()> {
+    let owner = host.state().owner;
+    let sender = ctx.sender();
+
+    ensure!(sender == owner, ContractError::Unauthorized);
+
+    let params: BurnParams = ctx.parameter_cursor().get()?;
+
+    burn(params, host, logger)?;
+
+    Ok(())
+}
+{% endif %}
+
+/// Check that a contract receiver is not the contract itself, other than at
+/// `wrap`. The contract calling itself is trusted as executing an approved
+/// operation or a migration, so a receiver must not be able to do it.
+fn ensure_receiver_allowed(ctx: &ReceiveContext, receiver: &Receiver) -> ContractResult<()> {
+    if let Receiver::Contract(address, {% if wrapped_ccd %}function{% else %}_{% endif %}) = receiver {
+        {% if wrapped_ccd %}
+        ensure!(
+            *address != ctx.self_address() || function.as_entrypoint_name() == WRAP_ENTRYPOINT,
+            CustomContractError::InvalidReceiver.into()
+        );
+        {% else %}
+        ensure!(*address != ctx.self_address(), CustomContractError::InvalidReceiver.into());
+        {% endif %}
+    }
+    Ok(())
+}
+
+{% if wrapped_ccd or dividends %}
+/// Send CCD of the contract to the receiver. A contract receiver is called
+/// with `data` as the parameter.
+fn send_ccd(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    receiver: Receiver,
+    data: &AdditionalData,
+    amount: Amount,
+) -> ContractResult<()> {
+    ensure_receiver_allowed(ctx, &receiver)?;
+
+    match receiver {
+        Receiver::Account(account) => {
+            host.invoke_transfer(&account, amount)
+                .map_err(|_| CustomContractError::InvokeTransferError)?;
+        }
+        Receiver::Contract(address, function) => {
+            host.invoke_contract_raw(
+                &address,
+                Parameter::new_unchecked(data.as_ref()),
+                function.as_entrypoint_name(),
+                amount,
+            )?;
+        }
+    }
+    Ok(())
+}
+{% endif %}
+
+{% if wrapped_ccd %}
+const WRAP_ENTRYPOINT: EntrypointName<'_> = EntrypointName::new_unchecked("wrap");
+
+#[derive(Serialize, SchemaType)]
+pub struct WrapParams {
+    /// The receiver of the wrapped CCD.
+    pub to:   Receiver,
+    /// Passed on to the hook of a contract receiver.
+    pub data: AdditionalData,
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct UnwrapParams {
+    pub amount:   ContractTokenAmount,
+    /// The owner of the wrapped CCD to unwrap.
+    pub owner:    Address,
+    /// The receiver of the CCD. A contract receiver is called with `data` as
+    /// the parameter.
+    pub receiver: Receiver,
+    pub data:     AdditionalData,
+}
+
+/// Mint the wrapped CCD token one-to-one with the deposited CCD.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "wrap",
+    parameter = "WrapParams",
+    error = "ContractError",
+    enable_logger,
+    mutable,
+    payable
+)]
+fn contract_wrap(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let params: WrapParams = ctx.parameter_cursor().get()?;
+    ensure_receiver_allowed(ctx, &params.to)?;
+
+    {% if pausable %}
+    host.state().ensure_not_paused(host.state().paused.wrap, Some(&WRAPPED_TOKEN_ID))?;
+    {% endif %}
+
+    let token_amount = ContractTokenAmount::from(amount.micro_ccd);
+    let to_address = params.to.address();
+
+    let (state, builder) = host.state_and_builder();
+    // The wrapped token is registered on init or migration, so its metadata
+    // URL is kept.
+    let metadata_url = state
+        .tokens
+        .get(&WRAPPED_TOKEN_ID)
+        .map(|x| x.to_owned())
+        .ok_or(ContractError::InvalidTokenId)?;
+    state.mint(&WRAPPED_TOKEN_ID, &metadata_url, token_amount, &to_address, builder);
+
+    logger.log(&Cis2Event::Mint(MintEvent {
+        token_id: WRAPPED_TOKEN_ID,
+        amount:   token_amount,
+        owner:    to_address,
+    }))?;
+
+    if let Receiver::Contract(address, function) = params.to {
+        let parameter = OnReceivingCis2Params {
+            token_id: WRAPPED_TOKEN_ID,
+            amount:   token_amount,
+            from:     ctx.sender(),
+            data:     params.data,
+        };
+        host.invoke_contract(&address, &parameter, function.as_entrypoint_name(), Amount::zero())?;
+    }
+
+    Ok(())
+}
+
+fn unwrap(
+    ctx: &ReceiveContext,
+    params: UnwrapParams,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    {% if pausable %}
+    host.state().ensure_not_paused(host.state().paused.unwrap, Some(&WRAPPED_TOKEN_ID))?;
+    {% endif %}
+
+    let state = host.state_mut();
+    let _: ContractTokenAmount = state.burn(&WRAPPED_TOKEN_ID, params.amount, &params.owner)?;
+
+    logger.log(&Cis2Event::Burn(BurnEvent {
+        token_id: WRAPPED_TOKEN_ID,
+        amount:   params.amount,
+        owner:    params.owner,
+    }))?;
+
+    send_ccd(ctx, host, params.receiver, &params.data, Amount::from_micro_ccd(params.amount.0))
+}
+
+/// Burn wrapped CCD of the owner and send the CCD to the receiver. The sender
+/// has to be the owner or an operator of the owner.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "unwrap",
+    parameter = "UnwrapParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_unwrap(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let sender = ctx.sender();
+    let params: UnwrapParams = ctx.parameter_cursor().get()?;
+
+    ensure!(
+        params.owner == sender || host.state().is_operator(&sender, &params.owner),
+        ContractError::Unauthorized
+    );
+
+    unwrap(ctx, params, host, logger)
+}
+
+/// The supply of wrapped CCD, which is the CCD held by the contract for
+/// `unwrap`. The CCD balance of the contract can be higher, e.g. by CCD
+/// distributed as dividends and not claimed yet.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "wrappedSupply",
+    return_value = "ContractTokenAmount"
+)]
+fn contract_wrapped_supply(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<ContractTokenAmount> {
+    Ok(host.state().token_balance.get(&WRAPPED_TOKEN_ID).map_or(0.into(), |x| *x))
+}
+{% endif %}
+
+type TransferParameter = TransferParams<ContractTokenId, ContractTokenAmount>;
+
+fn transfer(
+    ctx: &ReceiveContext,
+    transfer: concordium_cis2::Transfer<ContractTokenId, ContractTokenAmount>,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    ensure_receiver_allowed(ctx, &transfer.to)?;
+    let to_address = transfer.to.address();
+
+    {% if pausable %}
+    host.state().ensure_not_paused(host.state().paused.transfer, Some(&transfer.token_id))?;
+    {% endif %}
+
+    let (state, builder) = host.state_and_builder();
+
+    state.transfer(&transfer.token_id, transfer.amount, &transfer.from, &to_address, builder)?;
 
     logger.log(&Cis2Event::Transfer(TransferEvent {
         token_id: transfer.token_id,
@@ -1010,7 +3020,7 @@ fn transfer(
 }
 
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "transfer",
     parameter = "TransferParameter",
     error = "ContractError",
@@ -1032,7 +3042,7 @@ fn contract_transfer(
             ContractError::Unauthorized
         );
 
-        transfer(transfer_entry, host, logger)?;
+        transfer(ctx, transfer_entry, host, logger)?;
     }
     Ok(())
 }
@@ -1040,13 +3050,13 @@ fn contract_transfer(
 {% if sponsored %}
 /// Helper function that can be invoked at the front-end to serialize the
 /// `PermitMessage` before signing it in the wallet.
-#[receive(contract = "mint_wizard_{{ code }}_V{{ version }}", name = "serializationHelper", parameter = "PermitMessage")]
+#[receive(contract = "{{ contract_name }}", name = "serializationHelper", parameter = "PermitMessage")]
 fn contract_serialization_helper(_ctx: &ReceiveContext, _host: &Host<State>) -> ContractResult<()> {
     Ok(())
 }
 
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "viewMessageHash",
     parameter = "PermitParam",
     return_value = "[u8;32]",
@@ -1070,6 +3080,15 @@ fn contract_view_message_hash(
 
     cursor.read_exact(&mut message_bytes)?;
 
+    Ok(permit_message_hash(param.signer.as_ref(), &message_bytes, crypto_primitives))
+}
+
+/// The hash a signer signs for a serialized `PermitMessage`.
+fn permit_message_hash(
+    signer: &[u8; 32],
+    message_bytes: &[u8],
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> [u8; 32] {
     // The message signed in the Concordium browser wallet is prepended with the
     // `account` address and 8 zero bytes. Accounts in the Concordium browser wallet
     // can either sign a regular transaction (in that case the prepend is
@@ -1077,17 +3096,15 @@ fn contract_view_message_hash(
     // or sign a message (in that case the prepend is `account` address and 8 zero
     // bytes). Hence, the 8 zero bytes ensure that the user does not accidentally
     // sign a transaction. The account nonce is of type u64 (8 bytes).
+    // Public keys take the place of the address.
     let mut msg_prepend = [0; 32 + 8];
-    msg_prepend[0..32].copy_from_slice(param.signer.as_ref());
+    msg_prepend[0..32].copy_from_slice(signer);
     msg_prepend[32..40].copy_from_slice(&[0u8; 8]);
-    let message_hash =
-        crypto_primitives.hash_sha2_256(&[&msg_prepend[0..40], &message_bytes].concat()).0;
-
-    Ok(message_hash)
+    crypto_primitives.hash_sha2_256(&[&msg_prepend[0..40], message_bytes].concat()).0
 }
 
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "permit",
     parameter = "PermitParam",
     error = "ContractError",
@@ -1111,6 +3128,33 @@ fn contract_permit(
 
     let message = param.message;
 
+    check_permit_message(ctx, &message, nonce)?;
+
+    let message_hash = contract_view_message_hash(ctx, host, crypto_primitives)?;
+
+    let valid_signature =
+        host.check_account_signature(param.signer, &param.signature, &message_hash)?;
+    ensure!(valid_signature, CustomContractError::WrongSignature.into());
+
+    execute_permit(ctx, host, logger, PermitSigner::Account(param.signer), &message)?;
+
+    logger.log(&Event::Nonce(NonceEvent {
+        account: param.signer,
+        nonce,
+    }))?;
+
+    Ok(())
+}
+
+/// Check that the permit message is meant for this contract and nonce, and has
+/// not expired.
+fn check_permit_message(
+    ctx: &ReceiveContext,
+    message: &PermitMessage,
+    nonce: u64,
+) -> ContractResult<()> {
+    ensure_eq!(message.domain, PERMIT_DOMAIN, CustomContractError::WrongDomain.into());
+
     ensure_eq!(message.nonce, nonce, CustomContractError::NonceMismatch.into());
 
     ensure_eq!(
@@ -1121,11 +3165,19 @@ fn contract_permit(
 
     ensure!(message.timestamp > ctx.metadata().slot_time(), CustomContractError::Expired.into());
 
-    let message_hash = contract_view_message_hash(ctx, host, crypto_primitives)?;
+    Ok(())
+}
 
-    let valid_signature =
-        host.check_account_signature(param.signer, &param.signature, &message_hash)?;
-    ensure!(valid_signature, CustomContractError::WrongSignature.into());
+/// Execute the action of a verified permit message for the signer, and pay the
+/// fee of the message.
+fn execute_permit(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+    signer: PermitSigner,
+    message: &PermitMessage,
+) -> ContractResult<()> {
+    let sender = signer.address();
 
     match message.entry_point.as_entrypoint_name() {
         TRANSFER_ENTRYPOINT => {
@@ -1133,14 +3185,14 @@ fn contract_permit(
 
             for transfer_entry in transfers {
                 ensure!(
-                    transfer_entry.from.matches_account(&param.signer)
+                    transfer_entry.from == sender
                         || host
                             .state()
-                            .is_operator(&Address::from(param.signer), &transfer_entry.from),
+                            .is_operator(&sender, &transfer_entry.from),
                     ContractError::Unauthorized
                 );
 
-                transfer(transfer_entry, host, logger)?
+                transfer(ctx, transfer_entry, host, logger)?
             }
         }
         UPDATE_OPERATOR_ENTRYPOINT => {
@@ -1151,7 +3203,7 @@ fn contract_permit(
             for update in updates {
                 update_operator(
                     update.update,
-                    concordium_std::Address::Account(param.signer),
+                    sender,
                     update.operator,
                     state,
                     builder,
@@ -1163,13 +3215,28 @@ fn contract_permit(
         MINT_ENTRYPOINT => {
             let params: MintParams = from_bytes(&message.payload)?;
 
+            {% if multisig %}
+            // With multisig enabled, minting only runs as an approved operation.
+            ensure!(!host.state().multisig_enabled(), ContractError::Unauthorized);
+            {% endif %}
+
             ensure!(
                 {% if roles %}
-                host.state().has_role(&Address::from(param.signer), Roles::MINTER)
-                ||{% endif %} Address::from(param.signer) == Address::from(ctx.owner()),
+                host.state().has_role(
+                    &sender,
+                    Roles::MINTER,
+                    ctx.metadata().slot_time(),
+                )
+                ||{% endif %} sender == host.state().owner,
                 ContractError::Unauthorized
             );
 
+            {% if roles %}
+            if sender != host.state().owner {
+                host.state_mut().use_minter_quota(&sender, &params.tokens)?;
+            }
+            {% endif %}
+
             mint(params, host, logger)?;
         }
         {% endif %}
@@ -1178,26 +3245,348 @@ fn contract_permit(
             let params: BurnParams = from_bytes(&message.payload)?;
 
             ensure!(
-                params.owner.matches_account(&param.signer)
-                    || host.state().is_operator(&Address::from(param.signer), &params.owner),
+                params.owner == sender
+                    || host.state().is_operator(&sender, &params.owner),
                 ContractError::Unauthorized
             );
 
             burn(params, host, logger)?;
         }
         {% endif %}
-        _ => {
-            bail!(CustomContractError::WrongEntryPoint.into())
+        BUMP_NONCE_ENTRYPOINT => {
+            let next_nonce: u64 = from_bytes(&message.payload)?;
+
+            bump_nonce(signer, next_nonce, host.state_mut(), logger)?;
         }
-    }
+        {% if roles %}
+        GRANT_ROLE_ENTRYPOINT => {
+            let params: GrantRoleParams = from_bytes(&message.payload)?;
 
-    logger.log(&Event::Nonce(NonceEvent {
-        account: param.signer,
-        nonce,
-    }))?;
+            {% if multisig %}
+            // With multisig enabled, privileged actions only run as approved operations.
+            ensure!(!host.state().multisig_enabled(), ContractError::Unauthorized);
+            {% endif %}
+
+            let now = ctx.metadata().slot_time();
+            ensure!(
+                host.state().has_role(&sender, Roles::ADMIN, now),
+                ContractError::Unauthorized
+            );
+
+            let (state, state_builder) = host.state_and_builder();
+            grant_role(params, now, state, state_builder, logger)?;
+        }
+        REVOKE_ROLE_ENTRYPOINT => {
+            let params: RevokeRoleParams = from_bytes(&message.payload)?;
+
+            {% if multisig %}
+            // With multisig enabled, privileged actions only run as approved operations.
+            ensure!(!host.state().multisig_enabled(), ContractError::Unauthorized);
+            {% endif %}
+
+            ensure!(
+                host.state().has_role(
+                    &sender,
+                    Roles::ADMIN,
+                    ctx.metadata().slot_time(),
+                ),
+                ContractError::Unauthorized
+            );
+
+            revoke_role(params, host.state_mut(), logger)?;
+        }
+        {% endif %}
+        {% if pausable %}
+        SET_PAUSED_ENTRYPOINT => {
+            let params: PauseParams = from_bytes(&message.payload)?;
+
+            {% if multisig %}
+            // With multisig enabled, privileged actions only run as approved operations.
+            ensure!(!host.state().multisig_enabled(), ContractError::Unauthorized);
+            {% endif %}
+
+            ensure!(
+                {% if roles %}
+                host.state().has_role(
+                    &sender,
+                    Roles::PAUSER,
+                    ctx.metadata().slot_time(),
+                ),
+                {% else %}
+                sender == host.state().owner,
+                {% endif %}
+                ContractError::Unauthorized
+            );
+
+            set_paused(params, host.state_mut());
+        }
+        {% endif %}
+        {% if updates %}
+        UPGRADE_ENTRYPOINT => {
+            let params: UpgradeParams = from_bytes(&message.payload)?;
+
+            {% if multisig %}
+            // With multisig enabled, privileged actions only run as approved operations.
+            ensure!(!host.state().multisig_enabled(), ContractError::Unauthorized);
+            {% endif %}
+
+            ensure!(
+                {% if roles %}
+                host.state().has_role(
+                    &sender,
+                    Roles::UPGRADER,
+                    ctx.metadata().slot_time(),
+                ),
+                {% else %}
+                sender == host.state().owner,
+                {% endif %}
+                ContractError::Unauthorized
+            );
+
+            // The state of this call is written back when it returns, which
+            // would overwrite the state written by a migration.
+            ensure!(params.migrate.is_none(), CustomContractError::PermitMigrationUnsupported.into());
+            ensure_upgrade_allowed(host.state(), &params.module)?;
+
+            host.upgrade(params.module)?;
+        }
+        {% endif %}
+        {% if wrapped_ccd %}
+        UNWRAP_ENTRYPOINT => {
+            let params: UnwrapParams = from_bytes(&message.payload)?;
+
+            ensure!(
+                params.owner == sender || host.state().is_operator(&sender, &params.owner),
+                ContractError::Unauthorized
+            );
+
+            unwrap(ctx, params, host, logger)?;
+        }
+        {% endif %}
+        _ => {
+            bail!(CustomContractError::WrongEntryPoint.into())
+        }
+    }
+
+    // The fee is part of the signed message, so the sponsor is only paid if
+    // the permitted action succeeded.
+    if let Some(fee) = message.fee {
+        transfer(
+            ctx,
+            concordium_cis2::Transfer {
+                from:     sender,
+                to:       Receiver::from_account(ctx.invoker()),
+                token_id: fee.token_id,
+                amount:   fee.amount,
+                data:     AdditionalData::empty(),
+            },
+            host,
+            logger,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Execute multiple permits in one transaction. Each permit is executed as a
+/// call of `permit`, so a failed permit does not change the state. An atomic
+/// batch is rejected with the error of the first failed permit.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "permitBatch",
+    parameter = "PermitBatchParams",
+    return_value = "Vec<PermitResult>",
+    error = "ContractError",
+    mutable
+)]
+fn contract_permit_batch(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+) -> Result<Vec<PermitResult>, Reject> {
+    let params: PermitBatchParams = ctx.parameter_cursor().get()?;
+
+    let mut results = Vec::with_capacity(params.permits.len());
+    for permit in params.permits {
+        match host.invoke_contract(&ctx.self_address(), &permit, PERMIT_ENTRYPOINT, Amount::zero()) {
+            Ok(_) => results.push(PermitResult::Executed),
+            Err(CallContractError::LogicReject {
+                reason,
+                mut return_value,
+            }) if params.atomic => {
+                let mut error = vec![0u8; return_value.size() as usize];
+                return_value.read_exact(&mut error)?;
+                bail!(Reject {
+                    error_code:   core::num::NonZeroI32::new(reason).unwrap_abort(),
+                    return_value: Some(error),
+                });
+            }
+            Err(error) if params.atomic => {
+                bail!(ContractError::from(CustomContractError::from(error)).into())
+            }
+            Err(CallContractError::LogicReject {
+                reason,
+                ..
+            }) => results.push(PermitResult::Failed(reason)),
+            Err(_) => results.push(PermitResult::Failed(
+                Reject::from(CustomContractError::InvokeContractError).error_code.get(),
+            )),
+        }
+    }
+
+    Ok(results)
+}
+
+/// Register a public key to the sender, so that permits signed with the key
+/// act for the sender. This lets keys of smart contract wallets hold tokens
+/// through the wallet contract.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "registerPublicKey",
+    parameter = "RegisterPublicKeyParams",
+    error = "ContractError",
+    crypto_primitives,
+    mutable
+)]
+fn contract_register_public_key(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    let params: RegisterPublicKeyParams = ctx.parameter_cursor().get()?;
+    let sender = ctx.sender();
+
+    let valid_signature = crypto_primitives.verify_ed25519_signature(
+        params.public_key,
+        params.signature,
+        &to_bytes(&(ctx.self_address(), sender)),
+    );
+    ensure!(valid_signature, CustomContractError::WrongSignature.into());
+
+    let state = host.state_mut();
+    ensure!(
+        state.public_keys.get(&params.public_key).is_none_or(|address| *address == sender),
+        CustomContractError::PublicKeyAlreadyRegistered.into()
+    );
+    let _ = state.public_keys.insert(params.public_key, sender);
+
+    Ok(())
+}
+
+/// Remove the registration of a public key to the sender.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "unregisterPublicKey",
+    parameter = "PublicKeyEd25519",
+    error = "ContractError",
+    mutable
+)]
+fn contract_unregister_public_key(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+) -> ContractResult<()> {
+    let public_key: PublicKeyEd25519 = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    let address = state
+        .public_keys
+        .get(&public_key)
+        .map(|address| *address)
+        .ok_or(CustomContractError::PublicKeyNotRegistered)?;
+    ensure!(address == ctx.sender(), ContractError::Unauthorized);
+    state.public_keys.remove(&public_key);
 
     Ok(())
 }
+
+/// Get the hash a public key signs for a `PermitMessage`.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "viewPublicKeyMessageHash",
+    parameter = "PermitPublicKeyParam",
+    return_value = "[u8;32]",
+    error = "ContractError",
+    crypto_primitives
+)]
+fn contract_view_public_key_message_hash(
+    ctx: &ReceiveContext,
+    _host: &Host<State>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<[u8; 32]> {
+    let param: PermitPublicKeyParam = ctx.parameter_cursor().get()?;
+    Ok(permit_message_hash(&param.signer.0, &to_bytes(&param.message), crypto_primitives))
+}
+
+/// Execute a permit signed with a registered public key. The permit acts for
+/// the address the key is registered to, with the same rules as `permit`.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "permitPublicKey",
+    parameter = "PermitPublicKeyParam",
+    error = "ContractError",
+    crypto_primitives,
+    mutable,
+    enable_logger
+)]
+fn contract_permit_public_key(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    let param: PermitPublicKeyParam = ctx.parameter_cursor().get()?;
+
+    let address = host
+        .state()
+        .public_keys
+        .get(&param.signer)
+        .map(|address| *address)
+        .ok_or(CustomContractError::PublicKeyNotRegistered)?;
+
+    let mut entry = host.state_mut().public_key_nonces.entry(param.signer).or_insert_with(|| 0);
+
+    let nonce = *entry;
+    *entry += 1;
+    drop(entry);
+
+    let message = param.message;
+
+    check_permit_message(ctx, &message, nonce)?;
+
+    let message_hash = permit_message_hash(&param.signer.0, &to_bytes(&message), crypto_primitives);
+
+    let valid_signature =
+        crypto_primitives.verify_ed25519_signature(param.signer, param.signature, &message_hash);
+    ensure!(valid_signature, CustomContractError::WrongSignature.into());
+
+    let signer = PermitSigner::PublicKey(param.signer, address);
+    execute_permit(ctx, host, logger, signer, &message)?;
+
+    log_nonce(signer, nonce, logger)
+}
+
+/// Get the next nonce of each public key, which its next signed
+/// `PermitMessage` has to use.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "nonceOfPublicKey",
+    parameter = "Vec<PublicKeyEd25519>",
+    return_value = "NonceOfQueryResponse",
+    error = "ContractError"
+)]
+fn contract_nonce_of_public_key(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ContractResult<NonceOfQueryResponse> {
+    let queries: Vec<PublicKeyEd25519> = ctx.parameter_cursor().get()?;
+
+    let mut response: Vec<u64> = Vec::with_capacity(queries.len());
+    for public_key in queries {
+        let nonce =
+            host.state().public_key_nonces.get(&public_key).map(|nonce| *nonce).unwrap_or(0);
+        response.push(nonce);
+    }
+    Ok(NonceOfQueryResponse::from(response))
+}
 {% endif %}
 
 fn update_operator(
@@ -1209,7 +3598,7 @@ fn update_operator(
     logger: &mut impl HasLogger,
 ) -> ContractResult<()> {
     {% if pausable %}
-    ensure!(!state.paused, CustomContractError::Paused.into());
+    state.ensure_not_paused(state.paused.update_operator, None)?;
     {% endif %}
 
     match update {
@@ -1229,7 +3618,7 @@ fn update_operator(
 }
 
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "updateOperator",
     parameter = "UpdateOperatorParams",
     error = "ContractError",
@@ -1255,7 +3644,7 @@ pub type ContractBalanceOfQueryParams = BalanceOfQueryParams<ContractTokenId>;
 pub type ContractBalanceOfQueryResponse = BalanceOfQueryResponse<ContractTokenAmount>;
 
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "balanceOf",
     parameter = "ContractBalanceOfQueryParams",
     return_value = "ContractBalanceOfQueryResponse",
@@ -1276,7 +3665,7 @@ fn contract_balance_of(
 }
 
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "operatorOf",
     parameter = "OperatorOfQueryParams",
     return_value = "OperatorOfQueryResponse",
@@ -1299,7 +3688,7 @@ fn contract_operator_of(
 type ContractTokenMetadataQueryParams = TokenMetadataQueryParams<ContractTokenId>;
 
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "tokenMetadata",
     parameter = "ContractTokenMetadataQueryParams",
     return_value = "TokenMetadataQueryResponse",
@@ -1312,18 +3701,14 @@ fn contract_token_metadata(
     let params: ContractTokenMetadataQueryParams = ctx.parameter_cursor().get()?;
     let mut response = Vec::with_capacity(params.queries.len());
     for token_id in params.queries {
-        let metadata_url = match host.state().tokens.get(&token_id) {
-            Some(metadata_url) => metadata_url.clone(),
-            None => bail!(ContractError::InvalidTokenId),
-        };
-        response.push(metadata_url);
+        response.push(host.state().token_metadata_url(&token_id)?);
     }
     let result = TokenMetadataQueryResponse::from(response);
     Ok(result)
 }
 
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "supports",
     parameter = "SupportsQueryParams",
     return_value = "SupportsQueryResponse",
@@ -1349,7 +3734,7 @@ fn contract_supports(
 
 {% if sponsored %}
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "supportsPermit",
     parameter = "SupportsPermitQueryParams",
     return_value = "SupportsQueryResponse",
@@ -1372,144 +3757,2149 @@ fn contract_supports_permit(
     let result = SupportsQueryResponse::from(response);
     Ok(result)
 }
+
+/// Get the next nonce of each account, which its next signed `PermitMessage`
+/// has to use.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "nonceOf",
+    parameter = "VecOfAccountAddresses",
+    return_value = "NonceOfQueryResponse",
+    error = "ContractError"
+)]
+fn contract_nonce_of(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ContractResult<NonceOfQueryResponse> {
+    let params: VecOfAccountAddresses = ctx.parameter_cursor().get()?;
+
+    let mut response: Vec<u64> = Vec::with_capacity(params.queries.len());
+    for account in params.queries {
+        let nonce = host.state().nonces_registry.get(&account).map(|nonce| *nonce).unwrap_or(0);
+        response.push(nonce);
+    }
+    Ok(NonceOfQueryResponse::from(response))
+}
+
+/// Raise the next nonce of the sender to the given nonce, which cancels all
+/// signed messages with a lower nonce that have not been executed yet.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "bumpNonce",
+    parameter = "u64",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_bump_nonce(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let account = match ctx.sender() {
+        Address::Account(account) => account,
+        Address::Contract(_) => bail!(ContractError::Unauthorized),
+    };
+    let next_nonce: u64 = ctx.parameter_cursor().get()?;
+
+    bump_nonce(PermitSigner::Account(account), next_nonce, host.state_mut(), logger)
+}
+
+/// Logs a nonce event with the highest cancelled nonce.
+fn bump_nonce(
+    signer: PermitSigner,
+    next_nonce: u64,
+    state: &mut State,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    match signer {
+        PermitSigner::Account(account) => {
+            raise_nonce(&mut state.nonces_registry, account, next_nonce)?
+        }
+        PermitSigner::PublicKey(public_key, _) => {
+            raise_nonce(&mut state.public_key_nonces, public_key, next_nonce)?
+        }
+    }
+
+    log_nonce(signer, next_nonce - 1, logger)
+}
+
+fn raise_nonce<K: Serialize>(
+    nonces: &mut StateMap<K, u64, StateApi>,
+    key: K,
+    next_nonce: u64,
+) -> ContractResult<()> {
+    let mut entry = nonces.entry(key).or_insert_with(|| 0);
+    ensure!(next_nonce > *entry, CustomContractError::NonceMismatch.into());
+    *entry = next_nonce;
+    Ok(())
+}
+
+/// Log the nonce event of the signer.
+fn log_nonce(signer: PermitSigner, nonce: u64, logger: &mut impl HasLogger) -> ContractResult<()> {
+    match signer {
+        PermitSigner::Account(account) => logger.log(&Event::Nonce(NonceEvent {
+            account,
+            nonce,
+        }))?,
+        PermitSigner::PublicKey(public_key, _) => {
+            logger.log(&Event::PublicKeyNonce(PublicKeyNonceEvent {
+                public_key,
+                nonce,
+            }))?
+        }
+    }
+    Ok(())
+}
 {% endif %}
 
 /// Set the addresses for an implementation given a standard identifier and a
 /// list of contract addresses.
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
+    contract = "{{ contract_name }}",
     name = "setImplementors",
     parameter = "SetImplementorsParams",
     error = "ContractError",
     mutable
 )]
 fn contract_set_implementor(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
-    ensure!(ctx.sender().matches_account(&ctx.owner()), ContractError::Unauthorized);
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    ensure!(
+        multisig_call || ctx.sender() == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(ctx.sender() == host.state().owner, ContractError::Unauthorized);
+    {% endif %}
     let params: SetImplementorsParams = ctx.parameter_cursor().get()?;
     host.state_mut().set_implementors(params.id, params.implementors);
     Ok(())
 }
 
-{% if updates %}
+/// Set the base URL of the metadata of tokens stored with an empty URL.
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
-    name = "upgrade",
-    parameter = "UpgradeParams",
-    error = "CustomContractError",
-    low_level
+    contract = "{{ contract_name }}",
+    name = "setMetadataBase",
+    parameter = "MetadataBase",
+    error = "ContractError",
+    enable_logger,
+    mutable
 )]
-fn contract_upgrade(ctx: &ReceiveContext, host: &mut LowLevelHost) -> ContractResult<()> {
-    let state: State = host.state().read_root()?;
-
+fn contract_set_metadata_base(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
     let sender = ctx.sender();
 
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
     {% if roles %}
-    ensure!(state.has_role(&sender, Roles::UPGRADER), ContractError::Unauthorized);
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
     {% else %}
-    ensure!(sender.matches_account(&ctx.owner()), ContractError::Unauthorized);
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
     {% endif %}
 
-    let params: UpgradeParams = ctx.parameter_cursor().get()?;
-    host.upgrade(params.module)?;
-    if let Some((func, parameters)) = params.migrate {
-        host.invoke_contract_raw(
-            &ctx.self_address(),
-            parameters.as_parameter(),
-            func.as_entrypoint_name(),
-            Amount::zero(),
-        )?;
-    }
+    let metadata_base: MetadataBase = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    ensure!(!state.revealed, CustomContractError::MetadataRevealed.into());
+    ensure!(state.pending_reveal.is_none(), CustomContractError::RevealAlreadyPrepared.into());
+
+    logger.log(&Event::MetadataBaseUpdated(metadata_base.clone()))?;
+    state.metadata_base = Some(metadata_base);
+
     Ok(())
 }
-{% endif %}
 
-{% if pausable %}
+/// Hide the metadata of tokens stored with an empty URL behind a placeholder
+/// until `reveal` is called. The commitment is the SHA2-256 hash of the
+/// serialized `MetadataBase` the reveal will set.
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
-    name = "setPaused",
-    parameter = "SetPausedParams",
-    error = "CustomContractError",
+    contract = "{{ contract_name }}",
+    name = "prepareReveal",
+    parameter = "PendingReveal",
+    error = "ContractError",
+    enable_logger,
     mutable
 )]
-fn contract_set_paused(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+fn contract_prepare_reveal(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
     let sender = ctx.sender();
 
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
     {% if roles %}
-    ensure!(host.state().has_role(&sender, Roles::PAUSER), ContractError::Unauthorized);
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
     {% else %}
-    ensure!(sender.matches_account(&ctx.owner()), ContractError::Unauthorized);
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
     {% endif %}
 
-    let params: SetPausedParams = ctx.parameter_cursor().get()?;
+    let pending_reveal: PendingReveal = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    ensure!(!state.revealed, CustomContractError::MetadataRevealed.into());
+    ensure!(state.pending_reveal.is_none(), CustomContractError::RevealAlreadyPrepared.into());
 
-    host.state_mut().paused = params.paused;
+    logger.log(&Event::RevealPrepared(pending_reveal.clone()))?;
+    state.pending_reveal = Some(pending_reveal);
 
     Ok(())
 }
-{% endif %}
 
-{% if roles %}
+/// Reveal the metadata of tokens stored with an empty URL by setting the base
+/// URL. The base URL must match the commitment of the prepared reveal.
+/// Logs a `TokenMetadata` event for every revealed token.
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
-    name = "grantRole",
-    parameter = "GrantRoleParams",
+    contract = "{{ contract_name }}",
+    name = "reveal",
+    parameter = "MetadataBase",
+    error = "ContractError",
     enable_logger,
+    crypto_primitives,
     mutable
 )]
-fn contract_grant_role(
+fn contract_reveal(
     ctx: &ReceiveContext,
     host: &mut Host<State>,
     logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
 ) -> ContractResult<()> {
-    let params: GrantRoleParams = ctx.parameter_cursor().get()?;
-
-    let (state, state_builder) = host.state_and_builder();
-
     let sender = ctx.sender();
-    ensure!(state.has_role(&sender, Roles::ADMIN), ContractError::Unauthorized);
 
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let metadata_base: MetadataBase = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    let pending_reveal = state.pending_reveal.take().ok_or(CustomContractError::NoPendingReveal)?;
+    if let Some(commitment) = pending_reveal.commitment {
+        ensure_eq!(
+            crypto_primitives.hash_sha2_256(&to_bytes(&metadata_base)),
+            commitment,
+            CustomContractError::RevealCommitmentMismatch.into()
+        );
+    }
+
+    logger.log(&Event::MetadataBaseUpdated(metadata_base.clone()))?;
+    state.metadata_base = Some(metadata_base);
+    state.revealed = true;
+
+    let revealed: Vec<ContractTokenId> = state
+        .tokens
+        .iter()
+        .filter(|(_, metadata_url)| metadata_url.url.is_empty())
+        .map(|(token_id, _)| *token_id)
+        .collect();
+    for token_id in revealed {
+        logger.log(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(TokenMetadataEvent {
+            token_id,
+            metadata_url: state.token_metadata_url(&token_id)?,
+        }))?;
+    }
+
+    Ok(())
+}
+
+/// Helper function that can be invoked at the front-end to get the commitment
+/// of a `MetadataBase` before preparing a reveal.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "viewRevealCommitment",
+    parameter = "MetadataBase",
+    return_value = "HashSha2256",
+    error = "ContractError",
+    crypto_primitives
+)]
+fn contract_view_reveal_commitment(
+    ctx: &ReceiveContext,
+    _host: &Host<State>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<HashSha2256> {
+    let metadata_base: MetadataBase = ctx.parameter_cursor().get()?;
+    Ok(crypto_primitives.hash_sha2_256(&to_bytes(&metadata_base)))
+}
+
+/// Override the metadata URL of a token. An empty URL makes the token use the
+/// base URL again.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "setTokenMetadata",
+    parameter = "SetTokenMetadataParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_token_metadata(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let params: SetTokenMetadataParams = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    ensure!(state.contains_token(&params.token_id), ContractError::InvalidTokenId);
+    let _ = state.tokens.insert(params.token_id, params.metadata_url);
+
+    logger.log(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(TokenMetadataEvent {
+        token_id:     params.token_id,
+        metadata_url: state.token_metadata_url(&params.token_id)?,
+    }))?;
+
+    Ok(())
+}
+
+/// Set or remove on-chain attributes of a token.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "setAttributes",
+    parameter = "SetAttributesParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_attributes(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ATTRIBUTEUPDATER, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let params: SetAttributesParams = ctx.parameter_cursor().get()?;
+
+    let (state, state_builder) = host.state_and_builder();
+    ensure!(state.contains_token(&params.token_id), ContractError::InvalidTokenId);
+    state.set_attributes(&params.token_id, &params.attributes, state_builder);
+
+    if params.refresh_metadata {
+        logger.log(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(TokenMetadataEvent {
+            token_id:     params.token_id,
+            metadata_url: state.token_metadata_url(&params.token_id)?,
+        }))?;
+    }
+    logger.log(&Event::AttributesUpdated(params))?;
+
+    Ok(())
+}
+
+/// Get the on-chain attributes of tokens.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "tokenAttributes",
+    parameter = "Vec<ContractTokenId>",
+    return_value = "TokenAttributesQueryResponse",
+    error = "ContractError"
+)]
+fn contract_token_attributes(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ContractResult<TokenAttributesQueryResponse> {
+    let queries: Vec<ContractTokenId> = ctx.parameter_cursor().get()?;
+    let state = host.state();
+    let mut response = Vec::with_capacity(queries.len());
+    for token_id in queries {
+        ensure!(state.contains_token(&token_id), ContractError::InvalidTokenId);
+        response.push(state.token_attributes(&token_id));
+    }
+    Ok(response)
+}
+
+{% if updates %}
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "upgrade",
+    parameter = "UpgradeParams",
+    error = "CustomContractError",
+    low_level
+)]
+fn contract_upgrade(ctx: &ReceiveContext, host: &mut LowLevelHost) -> ContractResult<()> {
+    let state: State = host.state().read_root()?;
+
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, &state)?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}state.has_role(&sender, Roles::UPGRADER, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == state.owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let params: UpgradeParams = ctx.parameter_cursor().get()?;
+    ensure_upgrade_allowed(&state, &params.module)?;
+
+    host.upgrade(params.module)?;
+    if let Some((func, parameters)) = params.migrate {
+        host.invoke_contract_raw(
+            &ctx.self_address(),
+            parameters.as_parameter(),
+            func.as_entrypoint_name(),
+            Amount::zero(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Check that the module can be upgraded to directly, without proposing the
+/// upgrade first.
+fn ensure_upgrade_allowed(state: &State, module: &ModuleReference) -> ContractResult<()> {
+    ensure!(
+        state.upgrade_delay == Duration::from_millis(0),
+        CustomContractError::UpgradeTimelocked.into()
+    );
+    ensure!(
+        state.upgrade_allowlist.contains(module),
+        CustomContractError::UpgradeModuleNotAllowed.into()
+    );
+    Ok(())
+}
+
+/// Set the time an upgrade has to wait between being proposed and executed.
+/// The delay can only be increased.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "setUpgradeDelay",
+    parameter = "Duration",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_set_upgrade_delay(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::UPGRADER, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let delay: Duration = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    ensure!(delay >= state.upgrade_delay, CustomContractError::InvalidUpgradeDelay.into());
+    state.upgrade_delay = delay;
+
+    logger.log(&Event::UpgradeDelayUpdated(UpgradeDelayUpdatedEvent {
+        delay,
+    }))?;
+
+    Ok(())
+}
+
+/// Propose an upgrade which can be executed with `executeUpgrade` once the
+/// upgrade delay has passed.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "proposeUpgrade",
+    parameter = "UpgradeParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_propose_upgrade(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::UPGRADER, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let params: UpgradeParams = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    ensure!(
+        state.upgrade_allowlist.contains(&params.module),
+        CustomContractError::UpgradeModuleNotAllowed.into()
+    );
+    ensure!(
+        state.upgrade_proposals.get(&params.module).is_none(),
+        CustomContractError::UpgradeAlreadyProposed.into()
+    );
+
+    let executable_after = ctx
+        .metadata()
+        .slot_time()
+        .checked_add(state.upgrade_delay)
+        .ok_or(CustomContractError::InvalidUpgradeDelay)?;
+
+    let _ = state.upgrade_proposals.insert(params.module, UpgradeProposal {
+        migrate: params.migrate,
+        executable_after,
+    });
+
+    logger.log(&Event::UpgradeProposed(UpgradeProposedEvent {
+        module: params.module,
+        executable_after,
+    }))?;
+
+    Ok(())
+}
+
+/// Execute a proposed upgrade whose delay has passed.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "executeUpgrade",
+    parameter = "ModuleReference",
+    error = "ContractError",
+    low_level
+)]
+fn contract_execute_upgrade(ctx: &ReceiveContext, host: &mut LowLevelHost) -> ContractResult<()> {
+    let mut state: State = host.state().read_root()?;
+
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, &state)?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}state.has_role(&sender, Roles::UPGRADER, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == state.owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let module: ModuleReference = ctx.parameter_cursor().get()?;
+
+    // Maps write through to the contract state, so the proposal is removed
+    // without writing back the root of the state.
+    let proposal = state
+        .upgrade_proposals
+        .remove_and_get(&module)
+        .ok_or(CustomContractError::UpgradeNotProposed)?;
+    ensure!(
+        ctx.metadata().slot_time() >= proposal.executable_after,
+        CustomContractError::UpgradeTimelocked.into()
+    );
+    // The module may have been removed from the allowlist after the proposal.
+    ensure!(
+        state.upgrade_allowlist.contains(&module),
+        CustomContractError::UpgradeModuleNotAllowed.into()
+    );
+
+    host.upgrade(module)?;
+    if let Some((func, parameters)) = proposal.migrate {
+        host.invoke_contract_raw(
+            &ctx.self_address(),
+            parameters.as_parameter(),
+            func.as_entrypoint_name(),
+            Amount::zero(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Cancel a proposed upgrade.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "cancelUpgrade",
+    parameter = "ModuleReference",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_cancel_upgrade(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::UPGRADER, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let module: ModuleReference = ctx.parameter_cursor().get()?;
+
+    ensure!(
+        host.state_mut().upgrade_proposals.remove_and_get(&module).is_some(),
+        CustomContractError::UpgradeNotProposed.into()
+    );
+
+    logger.log(&Event::UpgradeCancelled(UpgradeCancelledEvent {
+        module,
+    }))?;
+
+    Ok(())
+}
+
+/// Add modules to or remove them from the upgrade allowlist.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "updateUpgradeAllowlist",
+    parameter = "UpdateUpgradeAllowlistParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_update_upgrade_allowlist(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let params: UpdateUpgradeAllowlistParams = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    for (module, allowed) in params.updates {
+        if allowed {
+            state.upgrade_allowlist.insert(module);
+        } else {
+            state.upgrade_allowlist.remove(&module);
+        }
+
+        logger.log(&Event::UpgradeAllowlistUpdated(UpgradeAllowlistUpdatedEvent {
+            module,
+            allowed,
+        }))?;
+    }
+
+    Ok(())
+}
+
+/// Get the modules the contract may be upgraded to.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "upgradeAllowlist",
+    return_value = "Vec<ModuleReference>"
+)]
+fn contract_upgrade_allowlist(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Vec<ModuleReference>> {
+    Ok(host.state().upgrade_allowlist.iter().map(|a| *a).collect())
+}
+
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct ViewUpgradeProposals {
+    pub upgrade_delay: Duration,
+    pub proposals:     Vec<(ModuleReference, UpgradeProposal)>,
+}
+
+/// View the upgrade delay and the upgrades waiting to be executed.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "viewUpgradeProposals",
+    return_value = "ViewUpgradeProposals"
+)]
+fn contract_view_upgrade_proposals(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<ViewUpgradeProposals> {
+    let state = host.state();
+    let proposals = state
+        .upgrade_proposals
+        .iter()
+        .map(|(module, proposal)| (*module, proposal.clone()))
+        .collect();
+
+    Ok(ViewUpgradeProposals {
+        upgrade_delay: state.upgrade_delay,
+        proposals,
+    })
+}
+
+{% if multisig %}
+/// The signers, the threshold, the next operation ID, the operations and the
+/// executing operation of multisig.
+type MultisigState = (
+    StateSet<AccountAddress, StateApi>,
+    u32,
+    u64,
+    StateMap<u64, MultisigOperation, StateApi>,
+    StateBox<Option<OwnedEntrypointName>, StateApi>,
+);
+
+{% endif %}
+{% if sponsored %}
+/// The nonces of accounts, the registered public keys and their nonces.
+type PermitNonces = (
+    StateMap<AccountAddress, u64, StateApi>,
+    StateMap<PublicKeyEd25519, Address, StateApi>,
+    StateMap<PublicKeyEd25519, u64, StateApi>,
+);
+
+{% endif %}
+{% if roles %}
+/// The roles of addresses and the members of roles, in the layout of a
+/// variant with different features.
+type LegacyRoles = (
+    StateMap<Address, LegacyAddressRoleState, StateApi>,
+    StateMap<u8, StateSet<Address, StateApi>, StateApi>,
+);
+
+{% endif %}
+{% if dividends %}
+/// The dividend pools of tokens and the dividend accounts of holders.
+type DividendState = (
+    StateMap<ContractTokenId, DividendPool, StateApi>,
+    StateMap<(Address, ContractTokenId), DividendAccount, StateApi>,
+);
+
+{% endif %}
+{% if snapshots %}
+/// The current snapshot ID and the balances and supplies at snapshots.
+type SnapshotState = (
+    u64,
+    StateMap<(Address, ContractTokenId, u64), ContractTokenAmount, StateApi>,
+    StateMap<(ContractTokenId, u64), ContractTokenAmount, StateApi>,
+);
+
+{% endif %}
+/// Migrate the state written by a variant with a subset of the features of
+/// this one. Pass it as the migration function when upgrading; it only
+/// accepts calls from the contract itself.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "migrate",
+    error = "ContractError",
+    {% if roles or wrapped_ccd %}
+    enable_logger,
+    {% endif %}
+    low_level
+)]
+fn contract_migrate(
+    ctx: &ReceiveContext,
+    host: &mut LowLevelHost,
+    {% if roles or wrapped_ccd %}
+    logger: &mut impl HasLogger,
+    {% endif %}
+) -> ContractResult<()> {
+    ensure!(ctx.sender().matches_contract(&ctx.self_address()), ContractError::Unauthorized);
+
+    let (state_api, state_builder) = host.state_and_builder();
+    let state_api: &StateApi = state_api;
+    let mut root = state_api.lookup_entry(&[]).unwrap_abort();
+
+    let version: u8 = root.get()?;
+    ensure_eq!(version, STATE_VERSION, CustomContractError::UnsupportedStateVersion.into());
+    let features: u32 = root.get()?;
+    ensure_eq!(features & !FEATURES, 0, CustomContractError::UnsupportedFeatures.into());
+    if features == FEATURES {
+        return Ok(());
+    }
+    let owner: Address = root.get()?;
+
+    // Read the old state field by field, skipping the fields of the features
+    // the old variant did not have.
+    let address_state: StateMap<Address, AddressState, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    let tokens: StateMap<ContractTokenId, MetadataUrl, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    let max_supply: StateMap<ContractTokenId, ContractTokenAmount, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    let token_balance: StateMap<ContractTokenId, ContractTokenAmount, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    let implementors: StateMap<StandardIdentifierOwned, Vec<ContractAddress>, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    let metadata_base: Option<MetadataBase> = root.get()?;
+    let pending_reveal: Option<PendingReveal> = root.get()?;
+    let revealed: bool = root.get()?;
+    let attributes: StateMap<ContractTokenId, StateMap<String, String, StateApi>, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    {% if mintable %}
+    let (minting_finalized, finalized_tokens, max_supply_locked, next_token_id): (
+        bool,
+        StateSet<ContractTokenId, StateApi>,
+        StateSet<ContractTokenId, StateApi>,
+        u32,
+    ) = if features & FEATURE_MINTABLE != 0 {
+        (
+            root.get()?,
+            DeserialWithState::deserial_with_state(state_api, &mut root)?,
+            DeserialWithState::deserial_with_state(state_api, &mut root)?,
+            root.get()?,
+        )
+    } else {
+        (false, state_builder.new_set(), state_builder.new_set(), 0)
+    };
+    {% endif %}
+    {% if sponsored %}
+    let (nonces_registry, public_keys, public_key_nonces): PermitNonces =
+        if features & FEATURE_SPONSORED != 0 {
+            (
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+            )
+        } else {
+            (state_builder.new_map(), state_builder.new_map(), state_builder.new_map())
+        };
+    {% endif %}
+    {% if pausable %}
+    let (paused, paused_tokens): (PausedOperations, StateSet<ContractTokenId, StateApi>) =
+        if features & FEATURE_PAUSABLE != 0 {
+            (
+                PausedOperations::deserial_with_features(features, &mut root)?,
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+            )
+        } else {
+            (PausedOperations::default(), state_builder.new_set())
+        };
+    {% endif %}
+    {% if roles %}
+    let legacy_roles: Option<LegacyRoles> = if features & FEATURE_ROLES != 0 {
+        Some((
+            DeserialWithState::deserial_with_state(state_api, &mut root)?,
+            DeserialWithState::deserial_with_state(state_api, &mut root)?,
+        ))
+    } else {
+        None
+    };
+    {% if mintable %}
+    let minter_quotas: StateMap<Address, MinterQuota, StateApi> =
+        if features & FEATURE_ROLES != 0 && features & FEATURE_MINTABLE != 0 {
+            DeserialWithState::deserial_with_state(state_api, &mut root)?
+        } else {
+            state_builder.new_map()
+        };
+    {% endif %}
+    {% endif %}
+    let (upgrade_delay, upgrade_proposals, upgrade_allowlist): (
+        Duration,
+        StateMap<ModuleReference, UpgradeProposal, StateApi>,
+        StateSet<ModuleReference, StateApi>,
+    ) = if features & FEATURE_UPDATES != 0 {
+        (
+            root.get()?,
+            DeserialWithState::deserial_with_state(state_api, &mut root)?,
+            DeserialWithState::deserial_with_state(state_api, &mut root)?,
+        )
+    } else {
+        (Duration::from_millis(0), state_builder.new_map(), state_builder.new_set())
+    };
+    {% if multisig %}
+    let (signers, threshold, next_operation_id, operations, executing_operation): MultisigState =
+        if features & FEATURE_MULTISIG != 0 {
+            (
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+                root.get()?,
+                root.get()?,
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+            )
+        } else {
+            (state_builder.new_set(), 0, 0, state_builder.new_map(), state_builder.new_box(None))
+        };
+    {% endif %}
+    {% if receive_tokens %}
+    let received_tokens: StateMap<(ContractAddress, TokenIdVec), ReceivedTokenAmount, StateApi> =
+        if features & FEATURE_RECEIVE_TOKENS != 0 {
+            DeserialWithState::deserial_with_state(state_api, &mut root)?
+        } else {
+            state_builder.new_map()
+        };
+    {% endif %}
+    {% if dividends %}
+    let (dividend_pools, dividend_accounts): DividendState =
+        if features & FEATURE_DIVIDENDS != 0 {
+            (
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+            )
+        } else {
+            (state_builder.new_map(), state_builder.new_map())
+        };
+    {% endif %}
+    {% if snapshots %}
+    let (snapshot_id, balance_snapshots, supply_snapshots): SnapshotState =
+        if features & FEATURE_SNAPSHOTS != 0 {
+            (
+                root.get()?,
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+                DeserialWithState::deserial_with_state(state_api, &mut root)?,
+            )
+        } else {
+            (0, state_builder.new_map(), state_builder.new_map())
+        };
+    {% endif %}
+    drop(root);
+
+    let {% if roles or wrapped_ccd %}mut {% endif %}state = State {
+        version: STATE_VERSION,
+        features: FEATURES,
+        owner,
+        state: address_state,
+        tokens,
+        max_supply,
+        token_balance,
+        implementors,
+        metadata_base,
+        pending_reveal,
+        revealed,
+        attributes,
+        {% if mintable %}
+        minting_finalized,
+        finalized_tokens,
+        max_supply_locked,
+        next_token_id,
+        {% endif %}
+        {% if sponsored %}
+        nonces_registry,
+        public_keys,
+        public_key_nonces,
+        {% endif %}
+        {% if pausable %}
+        paused,
+        paused_tokens,
+        {% endif %}
+        {% if roles %}
+        roles: state_builder.new_map(),
+        role_members: state_builder.new_map(),
+        {% if mintable %}
+        minter_quotas,
+        {% endif %}
+        {% endif %}
+        upgrade_delay,
+        upgrade_proposals,
+        upgrade_allowlist,
+        {% if multisig %}
+        signers,
+        threshold,
+        next_operation_id,
+        operations,
+        executing_operation,
+        {% endif %}
+        {% if receive_tokens %}
+        received_tokens,
+        {% endif %}
+        {% if dividends %}
+        dividend_pools,
+        dividend_accounts,
+        {% endif %}
+        {% if snapshots %}
+        snapshot_id,
+        balance_snapshots,
+        supply_snapshots,
+        {% endif %}
+    };
+
+    {% if roles %}
+    match legacy_roles {
+        Some((mut legacy_roles, mut legacy_role_members)) => {
+            // Roles are re-inserted, since their serialization depends on the
+            // features of the variant.
+            let roles_by_index = roles_of_features(features);
+            let mut grants = Vec::new();
+            for (address, address_roles) in legacy_roles.iter() {
+                for (index, expiry) in address_roles.roles.iter() {
+                    if let Some(role) = roles_by_index.get(usize::from(*index)) {
+                        grants.push((*address, *role, *expiry));
+                    }
+                }
+            }
+            legacy_roles.clear();
+            legacy_role_members.clear();
+            for (address, role, expiry) in grants {
+                state.grant_role(&address, role, expiry, state_builder);
+            }
+        }
+        None => {
+            // Without roles in the old variant, the owner becomes the admin
+            // just like on init.
+            state.grant_role(&owner, Roles::ADMIN, None, state_builder);
+            logger.log(&Event::GrantRole(GrantRoleEvent {
+                address: owner,
+                role:    Roles::ADMIN,
+                expiry:  None,
+            }))?;
+        }
+    }
+    {% endif %}
+
+    {% if wrapped_ccd %}
+    if features & FEATURE_WRAPPED_CCD == 0 {
+        state.register_wrapped_token()?;
+        // Without a metadata base, the URL is only known once it is set.
+        if let Ok(metadata_url) = state.token_metadata_url(&WRAPPED_TOKEN_ID) {
+            logger.log(&Cis2Event::TokenMetadata::<_, ContractTokenAmount>(TokenMetadataEvent {
+                token_id: WRAPPED_TOKEN_ID,
+                metadata_url,
+            }))?;
+        }
+    }
+    {% endif %}
+
+    let mut root = state_api.lookup_entry(&[]).unwrap_abort();
+    state.serial(&mut root).unwrap_abort();
+    let size = root.cursor_position();
+    root.truncate(size).unwrap_abort();
+
+    Ok(())
+}
+
+{% if migrate_v3 %}
+/// Migrate the state written by the V3 variant with the same binary code,
+/// which is not versioned. Pass it as the migration function when upgrading a
+/// V3 contract; it only accepts calls from the contract itself.
+#[receive(contract = "{{ contract_name }}", name = "migrateV3", error = "ContractError", low_level)]
+fn contract_migrate_v3(ctx: &ReceiveContext, host: &mut LowLevelHost) -> ContractResult<()> {
+    ensure!(ctx.sender().matches_contract(&ctx.self_address()), ContractError::Unauthorized);
+
+    let (state_api, state_builder) = host.state_and_builder();
+    let state_api: &StateApi = state_api;
+    let mut root = state_api.lookup_entry(&[]).unwrap_abort();
+
+    // The V3 layout starts with the maps shared by all variants, followed by
+    // the fields of the features of the binary code.
+    let address_state: StateMap<Address, AddressState, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    let tokens: StateMap<ContractTokenId, MetadataUrl, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    let max_supply: StateMap<ContractTokenId, ContractTokenAmount, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    let mut token_balance: StateMap<ContractTokenId, ContractTokenAmount, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    let implementors: StateMap<StandardIdentifierOwned, Vec<ContractAddress>, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    {% if sponsored %}
+    let nonces_registry: StateMap<AccountAddress, u64, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    {% endif %}
+    {% if pausable %}
+    let paused: bool = root.get()?;
+    {% endif %}
+    {% if roles %}
+    let mut v3_roles: StateMap<Address, V3AddressRoleState, StateApi> =
+        DeserialWithState::deserial_with_state(state_api, &mut root)?;
+    {% endif %}
+    drop(root);
+
+    // V3 did not lower `token_balance` on burns, so the circulating supply is
+    // recounted from the balances.
+    let mut supplies: BTreeMap<ContractTokenId, ContractTokenAmount> = BTreeMap::new();
+    for (_address, address_state) in address_state.iter() {
+        for (token_id, amount) in address_state.balances.iter() {
+            *supplies.entry(*token_id).or_insert(0.into()) += *amount;
+        }
+    }
+    for (token_id, _metadata_url) in tokens.iter() {
+        let supply = supplies.get(&*token_id).copied().unwrap_or(0.into());
+        let _ = token_balance.insert(*token_id, supply);
+    }
+
+    // V3 had no owner in the state and used the owner of the instance.
+    let mut state = State::empty(Address::Account(ctx.owner()), state_builder);
+    state.state = address_state;
+    state.tokens = tokens;
+    state.max_supply = max_supply;
+    state.token_balance = token_balance;
+    state.implementors = implementors;
+    {% if sponsored %}
+    state.nonces_registry = nonces_registry;
+    {% endif %}
+    {% if pausable %}
+    state.paused = PausedOperations::uniform(paused);
+    {% endif %}
+    {% if roles %}
+    let roles_by_index = roles_of_features(FEATURES);
+    let mut grants = Vec::new();
+    for (address, address_roles) in v3_roles.iter() {
+        for index in address_roles.roles.iter() {
+            if let Some(role) = roles_by_index.get(usize::from(*index)) {
+                grants.push((*address, *role));
+            }
+        }
+    }
+    v3_roles.clear();
+    for (address, role) in grants {
+        state.grant_role(&address, role, None, state_builder);
+    }
+    {% endif %}
+
+    {% if wrapped_ccd %}
+    // V3 had no wrapped CCD and no metadata base, so there is no URL to log
+    // until `setMetadataBase` is called.
+    state.register_wrapped_token()?;
+    {% endif %}
+
+    let mut root = state_api.lookup_entry(&[]).unwrap_abort();
+    state.serial(&mut root).unwrap_abort();
+    let size = root.cursor_position();
+    root.truncate(size).unwrap_abort();
+
+    Ok(())
+}
+{% endif %}
+{% endif %}
+
+{% if pausable %}
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "setPaused",
+    parameter = "PauseParams",
+    error = "CustomContractError",
+    mutable
+)]
+fn contract_set_paused(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::PAUSER, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    // A single byte can only be the legacy `SetPausedParams`.
+    let params: PauseParams = if ctx.parameter_cursor().size() == 1 {
+        let params: SetPausedParams = ctx.parameter_cursor().get()?;
+        PauseParams {
+            operations: PausedOperations::uniform(params.paused),
+            tokens:     Vec::new(),
+        }
+    } else {
+        ctx.parameter_cursor().get()?
+    };
+
+    set_paused(params, host.state_mut());
+
+    Ok(())
+}
+
+fn set_paused(params: PauseParams, state: &mut State) {
+    state.paused = params.operations;
+    for (token_id, paused) in params.tokens {
+        if paused {
+            state.paused_tokens.insert(token_id);
+        } else {
+            state.paused_tokens.remove(&token_id);
+        }
+    }
+}
+{% endif %}
+
+{% if roles %}
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "grantRole",
+    parameter = "GrantRoleParams",
+    enable_logger,
+    mutable
+)]
+fn contract_grant_role(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let params: GrantRoleParams = ctx.parameter_cursor().get()?;
+
+    let (state, state_builder) = host.state_and_builder();
+
+    let sender = ctx.sender();
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, state)?;
+    ensure!(
+        multisig_call || state.has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        state.has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    grant_role(params, ctx.metadata().slot_time(), state, state_builder, logger)
+}
+
+fn grant_role(
+    params: GrantRoleParams,
+    now: Timestamp,
+    state: &mut State,
+    state_builder: &mut StateBuilder,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    // An expired grant can be renewed.
+    ensure!(
+        !state.has_role(&params.address, params.role, now),
+        CustomContractError::RoleWasAlreadyGranted.into()
+    );
+    ensure!(
+        params.expiry.is_none_or(|expiry| expiry > now),
+        CustomContractError::InvalidRoleExpiry.into()
+    );
+
+    state.grant_role(&params.address, params.role, params.expiry, state_builder);
+    logger.log(&Event::GrantRole(GrantRoleEvent {
+        address: params.address,
+        role:    params.role,
+        expiry:  params.expiry,
+    }))?;
+    Ok(())
+}
+
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "revokeRole",
+    parameter = "RevokeRoleParams",
+    enable_logger,
+    mutable
+)]
+fn contract_revoke_role(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let params: RevokeRoleParams = ctx.parameter_cursor().get()?;
+
+    let (state, _) = host.state_and_builder();
+
+    let sender = ctx.sender();
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, state)?;
+    ensure!(
+        multisig_call || state.has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        state.has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    revoke_role(params, state, logger)
+}
+
+fn revoke_role(
+    params: RevokeRoleParams,
+    state: &mut State,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    state.ensure_revoke_role(&params.address, params.role)?;
+    logger.log(&Event::RevokeRole(RevokeRoleEvent {
+        address: params.address,
+        role:    params.role,
+    }))?;
+    Ok(())
+}
+
+/// Give up a role held by the sender.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "renounceRole",
+    parameter = "Roles",
+    enable_logger,
+    mutable
+)]
+fn contract_renounce_role(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let role: Roles = ctx.parameter_cursor().get()?;
+
+    let sender = ctx.sender();
+    host.state_mut().ensure_revoke_role(&sender, role)?;
+    logger.log(&Event::RevokeRole(RevokeRoleEvent {
+        address: sender,
+        role,
+    }))?;
+    Ok(())
+}
+
+/// Check whether an address holds a role.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "hasRole",
+    parameter = "HasRoleQueryParams",
+    return_value = "bool",
+    error = "ContractError"
+)]
+fn contract_has_role(ctx: &ReceiveContext, host: &Host<State>) -> ContractResult<bool> {
+    let params: HasRoleQueryParams = ctx.parameter_cursor().get()?;
+    Ok(host.state().has_role(&params.address, params.role, ctx.metadata().slot_time()))
+}
+
+/// Get the addresses holding a role with the time their grant expires at, one
+/// page at a time. Expired grants are listed until they are revoked.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "roleMembers",
+    parameter = "RoleMembersQueryParams",
+    return_value = "Vec<(Address, Option<Timestamp>)>",
+    error = "ContractError"
+)]
+fn contract_role_members(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ContractResult<Vec<(Address, Option<Timestamp>)>> {
+    let params: RoleMembersQueryParams = ctx.parameter_cursor().get()?;
+    let state = host.state();
+    let members = match state.role_members.get(&params.role) {
+        None => Vec::new(),
+        Some(members) => members
+            .iter()
+            .skip(params.skip as usize)
+            .take(params.limit as usize)
+            .map(|address| (*address, state.role_grant(&address, params.role).flatten()))
+            .collect(),
+    };
+    Ok(members)
+}
+
+{% if mintable %}
+/// Set or remove the limits of a minter.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "setMinterQuota",
+    parameter = "SetMinterQuotaParams",
+    error = "ContractError",
+    mutable
+)]
+fn contract_set_minter_quota(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+
+    let params: SetMinterQuotaParams = ctx.parameter_cursor().get()?;
+
+    let (state, state_builder) = host.state_and_builder();
+    state.minter_quotas.remove(&params.minter);
+    if let Some(limits) = params.quota {
+        let mut per_token = state_builder.new_map();
+        for (token_id, amount) in limits.per_token {
+            let _ = per_token.insert(token_id, amount);
+        }
+        let _ = state.minter_quotas.insert(params.minter, MinterQuota {
+            total: limits.total,
+            per_token,
+            token_id_ranges: limits.token_id_ranges,
+        });
+    }
+
+    Ok(())
+}
+
+/// Get the remaining quota of a minter. `None` if the minter is not limited.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "viewMinterQuota",
+    parameter = "Address",
+    return_value = "Option<MinterQuotaParams>",
+    error = "ContractError"
+)]
+fn contract_view_minter_quota(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ContractResult<Option<MinterQuotaParams>> {
+    let minter: Address = ctx.parameter_cursor().get()?;
+    let quota = host.state().minter_quotas.get(&minter).map(|quota| MinterQuotaParams {
+        total:           quota.total,
+        per_token:       quota.per_token.iter().map(|(id, amount)| (*id, *amount)).collect(),
+        token_id_ranges: quota.token_id_ranges.clone(),
+    });
+    Ok(quota)
+}
+{% endif %}
+{% endif %}
+
+{% if multisig %}
+/// Check whether the call executes an approved multisig operation, i.e. the
+/// contract calls the entrypoint of the operation `approveOperation` is
+/// executing. Fails for any other caller if multisig is enabled.
+fn check_multisig_call(ctx: &ReceiveContext, state: &State) -> ContractResult<bool> {
+    if ctx.sender().matches_contract(&ctx.self_address())
+        && state.executing_operation.get().as_ref() == Some(&ctx.named_entrypoint())
+    {
+        return Ok(true);
+    }
+    ensure!(!state.multisig_enabled(), ContractError::Unauthorized);
+    Ok(false)
+}
+
+/// Set the signers and the threshold. Enabling multisig is a privileged
+/// action; once enabled, changes have to be approved as an operation.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "updateMultisig",
+    parameter = "UpdateMultisigParams",
+    error = "ContractError",
+    mutable
+)]
+fn contract_update_multisig(ctx: &ReceiveContext, host: &mut Host<State>) -> ContractResult<()> {
+    let sender = ctx.sender();
+
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% if roles %}
     ensure!(
-        !state.has_role(&params.address, params.role),
-        CustomContractError::RoleWasAlreadyGranted.into()
+        multisig_call || host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
     );
+    {% else %}
+    ensure!(
+        multisig_call || sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
 
-    state.grant_role(&params.address, params.role, state_builder);
-    logger.log(&Event::GrantRole(GrantRoleEvent {
-        address: params.address,
-        role:    params.role,
+    let params: UpdateMultisigParams = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    state.signers.clear();
+    for signer in params.signers {
+        state.signers.insert(signer);
+    }
+    let signers = state.signers.iter().count() as u32;
+    ensure!(
+        params.threshold <= signers && (params.threshold > 0 || signers == 0),
+        CustomContractError::InvalidThreshold.into()
+    );
+    state.threshold = params.threshold;
+
+    Ok(())
+}
+
+/// Propose a call of a privileged entrypoint. The proposer approves the
+/// operation right away. Returns the ID of the operation.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "proposeOperation",
+    parameter = "ProposeOperationParams",
+    return_value = "u64",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_propose_operation(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<u64> {
+    let params: ProposeOperationParams = ctx.parameter_cursor().get()?;
+
+    let proposer = match ctx.sender() {
+        Address::Account(account) => account,
+        Address::Contract(_) => bail!(CustomContractError::NotASigner.into()),
+    };
+
+    let state = host.state_mut();
+    ensure!(state.multisig_enabled(), CustomContractError::MultisigDisabled.into());
+    ensure!(state.signers.contains(&proposer), CustomContractError::NotASigner.into());
+
+    let operation_id = state.next_operation_id;
+    state.next_operation_id += 1;
+
+    logger.log(&Event::OperationProposed(OperationProposedEvent {
+        operation_id,
+        proposer,
+        entrypoint: params.entrypoint.clone(),
+    }))?;
+
+    // With a threshold of one, the operation is executed by the first
+    // `approveOperation`, which runs on the low level host.
+    let _ = state.operations.insert(operation_id, MultisigOperation {
+        entrypoint: params.entrypoint,
+        parameter:  params.parameter,
+        approvals:  vec![proposer],
+    });
+
+    logger.log(&Event::OperationApproved(OperationApprovedEvent {
+        operation_id,
+        signer: proposer,
+    }))?;
+
+    Ok(operation_id)
+}
+
+/// Record the approvals of the signers and execute the operation once the
+/// threshold is met. Runs on the low level host, so the executed entrypoint
+/// can upgrade the contract and migrate its state.
+fn approve_operation(
+    ctx: &ReceiveContext,
+    host: &mut LowLevelHost,
+    logger: &mut impl HasLogger,
+    operation_id: u64,
+    signers: Vec<AccountAddress>,
+) -> ContractResult<()> {
+    let mut state: State = host.state().read_root()?;
+
+    ensure!(state.multisig_enabled(), CustomContractError::MultisigDisabled.into());
+
+    // Maps write through to the contract state, so the operation is updated
+    // without writing back the root of the state.
+    let mut operation = state
+        .operations
+        .remove_and_get(&operation_id)
+        .ok_or(CustomContractError::UnknownOperation)?;
+
+    for signer in signers {
+        ensure!(state.signers.contains(&signer), CustomContractError::NotASigner.into());
+        if !operation.approvals.contains(&signer) {
+            operation.approvals.push(signer);
+            logger.log(&Event::OperationApproved(OperationApprovedEvent {
+                operation_id,
+                signer,
+            }))?;
+        }
+    }
+
+    // Approvals of accounts that were removed as signers do not count.
+    let approvals =
+        operation.approvals.iter().filter(|signer| state.signers.contains(signer)).count() as u32;
+
+    if approvals < state.threshold {
+        let _ = state.operations.insert(operation_id, operation);
+        return Ok(());
+    }
+
+    let _ = state.executing_operation.replace(Some(operation.entrypoint.clone()));
+    host.invoke_contract_raw(
+        &ctx.self_address(),
+        operation.parameter.as_parameter(),
+        operation.entrypoint.as_entrypoint_name(),
+        Amount::zero(),
+    )?;
+    // The executed entrypoint invalidates the state entries read before.
+    let mut state: State = host.state().read_root()?;
+    let _ = state.executing_operation.replace(None);
+
+    logger.log(&Event::OperationExecuted(OperationExecutedEvent {
+        operation_id,
     }))?;
+
     Ok(())
 }
 
+/// Approve an operation as the sender.
 #[receive(
-    contract = "mint_wizard_{{ code }}_V{{ version }}",
-    name = "revokeRole",
-    parameter = "RevokeRoleParams",
+    contract = "{{ contract_name }}",
+    name = "approveOperation",
+    parameter = "u64",
+    error = "ContractError",
+    enable_logger,
+    low_level
+)]
+fn contract_approve_operation(
+    ctx: &ReceiveContext,
+    host: &mut LowLevelHost,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let operation_id: u64 = ctx.parameter_cursor().get()?;
+
+    let signer = match ctx.sender() {
+        Address::Account(account) => account,
+        Address::Contract(_) => bail!(CustomContractError::NotASigner.into()),
+    };
+
+    approve_operation(ctx, host, logger, operation_id, vec![signer])
+}
+
+/// Helper function that can be invoked at the front-end to get the hash a
+/// signer signs to approve an operation off-chain. The hash is built like the
+/// hash of a `PermitMessage`.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "viewApprovalMessageHash",
+    parameter = "SignedApproval",
+    return_value = "[u8;32]",
+    error = "ContractError",
+    crypto_primitives
+)]
+fn contract_view_approval_message_hash(
+    ctx: &ReceiveContext,
+    _host: &Host<State>,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<[u8; 32]> {
+    let param: SignedApproval = ctx.parameter_cursor().get()?;
+    Ok(approval_message_hash(&param, crypto_primitives))
+}
+
+/// The message is prepended with the signer and 8 zero bytes, just like the
+/// message of `permit`, so it can not be mistaken for a transaction.
+fn approval_message_hash(
+    param: &SignedApproval,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> [u8; 32] {
+    let mut msg_prepend = [0; 32 + 8];
+    msg_prepend[0..32].copy_from_slice(param.signer.as_ref());
+    crypto_primitives.hash_sha2_256(&[&msg_prepend[0..40], &to_bytes(&param.message)].concat()).0
+}
+
+/// Approve an operation with signatures of the signers, which anyone can
+/// submit.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "approveOperationSigned",
+    parameter = "ApproveOperationSignedParams",
+    error = "ContractError",
+    crypto_primitives,
+    enable_logger,
+    low_level
+)]
+fn contract_approve_operation_signed(
+    ctx: &ReceiveContext,
+    host: &mut LowLevelHost,
+    logger: &mut impl HasLogger,
+    crypto_primitives: &impl HasCryptoPrimitives,
+) -> ContractResult<()> {
+    let params: ApproveOperationSignedParams = ctx.parameter_cursor().get()?;
+
+    let mut operation_id = None;
+    let mut signers = Vec::with_capacity(params.approvals.len());
+    for approval in params.approvals {
+        ensure_eq!(
+            approval.message.contract_address,
+            ctx.self_address(),
+            CustomContractError::WrongContract.into()
+        );
+        ensure!(
+            approval.message.timestamp > ctx.metadata().slot_time(),
+            CustomContractError::Expired.into()
+        );
+        // All approvals have to be for the same operation.
+        ensure!(
+            operation_id.is_none_or(|id| id == approval.message.operation_id),
+            CustomContractError::UnknownOperation.into()
+        );
+        operation_id = Some(approval.message.operation_id);
+
+        let message_hash = approval_message_hash(&approval, crypto_primitives);
+        let valid_signature =
+            host.check_account_signature(approval.signer, &approval.signature, &message_hash)?;
+        ensure!(valid_signature, CustomContractError::WrongSignature.into());
+
+        signers.push(approval.signer);
+    }
+
+    let operation_id = operation_id.ok_or(CustomContractError::UnknownOperation)?;
+    approve_operation(ctx, host, logger, operation_id, signers)
+}
+
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct ViewMultisig {
+    pub signers:    Vec<AccountAddress>,
+    pub threshold:  u32,
+    pub operations: Vec<(u64, MultisigOperation)>,
+}
+
+/// View the signers, the threshold and the operations waiting for approvals.
+#[receive(contract = "{{ contract_name }}", name = "viewMultisig", return_value = "ViewMultisig")]
+fn contract_view_multisig(_ctx: &ReceiveContext, host: &Host<State>) -> ReceiveResult<ViewMultisig> {
+    let state = host.state();
+    Ok(ViewMultisig {
+        signers:    state.signers.iter().map(|a| *a).collect(),
+        threshold:  state.threshold,
+        operations: state.operations.iter().map(|(id, operation)| (*id, operation.clone())).collect(),
+    })
+}
+{% endif %}
+
+{% if receive_tokens %}
+/// The amount type of received tokens. Transfers of larger amounts are
+/// rejected.
+pub type ReceivedTokenAmount = TokenAmountU64;
+
+type OnReceivingCis2Parameter = OnReceivingCis2Params<TokenIdVec, ReceivedTokenAmount>;
+
+/// Record tokens of another CIS-2 contract transferred to this contract. The
+/// sender is the contract of the tokens.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "onReceivingCIS2",
+    parameter = "OnReceivingCis2Parameter",
+    error = "ContractError",
     enable_logger,
     mutable
 )]
-fn contract_revoke_role(
+fn contract_on_receiving_cis2(
     ctx: &ReceiveContext,
     host: &mut Host<State>,
     logger: &mut impl HasLogger,
 ) -> ContractResult<()> {
-    let params: RevokeRoleParams = ctx.parameter_cursor().get()?;
+    let contract = match ctx.sender() {
+        Address::Contract(contract) => contract,
+        Address::Account(_) => bail!(CustomContractError::ContractOnly.into()),
+    };
+
+    let params: OnReceivingCis2Parameter = ctx.parameter_cursor().get()?;
+
+    let mut received = host
+        .state_mut()
+        .received_tokens
+        .entry((contract, params.token_id.clone()))
+        .or_insert(ReceivedTokenAmount::from(0));
+    *received += params.amount;
+    drop(received);
+
+    logger.log(&Event::TokensReceived(TokensReceivedEvent {
+        contract,
+        token_id: params.token_id,
+        amount: params.amount,
+        from: params.from,
+    }))?;
 
-    let (state, _) = host.state_and_builder();
+    Ok(())
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct RecoverTokensParams {
+    /// The CIS-2 contract of the tokens.
+    pub contract: ContractAddress,
+    pub token_id: TokenIdVec,
+    pub amount:   ReceivedTokenAmount,
+    pub to:       Receiver,
+}
 
+/// Transfer tokens of another CIS-2 contract held by this contract, by calling
+/// `transfer` of that contract. Tokens that were not received by
+/// `onReceivingCIS2` can be recovered too; the recorded amount is lowered by at
+/// most the recovered amount.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "recoverTokens",
+    parameter = "RecoverTokensParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_recover_tokens(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
     let sender = ctx.sender();
-    ensure!(state.has_role(&sender, Roles::ADMIN), ContractError::Unauthorized);
 
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
     ensure!(
-        state.has_role(&params.address, params.role),
-        CustomContractError::RoleWasNotGranted.into()
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
     );
+    {% endif %}
 
-    state.revoke_role(&params.address, params.role);
-    logger.log(&Event::RevokeRole(RevokeRoleEvent {
-        address: params.address,
-        role:    params.role,
+    let params: RecoverTokensParams = ctx.parameter_cursor().get()?;
+
+    let key = (params.contract, params.token_id.clone());
+    if let Some(received) = host.state().received_tokens.get(&key).map(|amount| *amount) {
+        if received.0 > params.amount.0 {
+            let _ = host
+                .state_mut()
+                .received_tokens
+                .insert(key, ReceivedTokenAmount::from(received.0 - params.amount.0));
+        } else {
+            host.state_mut().received_tokens.remove(&key);
+        }
+    }
+
+    let to = params.to.address();
+    let transfer = TransferParams::<TokenIdVec, ReceivedTokenAmount>::from(vec![
+        concordium_cis2::Transfer {
+            token_id: params.token_id.clone(),
+            amount:   params.amount,
+            from:     Address::Contract(ctx.self_address()),
+            to:       params.to,
+            data:     AdditionalData::empty(),
+        },
+    ]);
+    host.invoke_contract(
+        &params.contract,
+        &transfer,
+        EntrypointName::new_unchecked("transfer"),
+        Amount::zero(),
+    )?;
+
+    logger.log(&Event::TokensRecovered(TokensRecoveredEvent {
+        contract: params.contract,
+        token_id: params.token_id,
+        amount: params.amount,
+        to,
+    }))?;
+
+    Ok(())
+}
+
+#[derive(Serialize, SchemaType, PartialEq, Eq, Debug)]
+pub struct ViewReceivedTokens {
+    pub contract: ContractAddress,
+    pub token_id: TokenIdVec,
+    pub amount:   ReceivedTokenAmount,
+}
+
+/// View the recorded amounts of received tokens by contract and token ID.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "viewReceivedTokens",
+    return_value = "Vec<ViewReceivedTokens>"
+)]
+fn contract_view_received_tokens(
+    _ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ReceiveResult<Vec<ViewReceivedTokens>> {
+    Ok(host
+        .state()
+        .received_tokens
+        .iter()
+        .map(|(key, amount)| ViewReceivedTokens {
+            contract: key.0,
+            token_id: key.1.clone(),
+            amount:   *amount,
+        })
+        .collect())
+}
+{% endif %}
+
+{% if dividends %}
+/// Distribute the deposited CCD to the holders of a token, in proportion to
+/// their balances.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "distribute",
+    parameter = "ContractTokenId",
+    error = "ContractError",
+    enable_logger,
+    mutable,
+    payable
+)]
+fn contract_distribute(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    amount: Amount,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let token_id: ContractTokenId = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    ensure!(state.contains_token(&token_id), ContractError::InvalidTokenId);
+
+    let supply = state.token_balance.get(&token_id).map_or(0.into(), |x| *x);
+    ensure!(supply > 0u64.into(), CustomContractError::NoDividendHolders.into());
+    let supply = u128::from(supply.0);
+    let mut pool = state.dividend_pools.entry(token_id).or_insert(DividendPool {
+        per_unit:  0,
+        remainder: 0,
+    });
+    let scaled = u128::from(amount.micro_ccd) * DIVIDEND_MAGNITUDE + pool.remainder;
+    pool.per_unit += scaled / supply;
+    pool.remainder = scaled % supply;
+    drop(pool);
+
+    logger.log(&Event::DividendDistributed(DividendDistributedEvent {
+        token_id,
+        amount,
+        from: ctx.sender(),
     }))?;
+
     Ok(())
 }
+
+#[derive(Serialize, SchemaType)]
+pub struct ClaimParams {
+    pub token_id: ContractTokenId,
+    /// The receiver of the CCD. A contract receiver is called with `data` as
+    /// the parameter.
+    pub receiver: Receiver,
+    pub data:     AdditionalData,
+}
+
+/// Claim the dividends of the sender for holding a token.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "claim",
+    parameter = "ClaimParams",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_claim(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<()> {
+    let holder = ctx.sender();
+    let params: ClaimParams = ctx.parameter_cursor().get()?;
+
+    let state = host.state_mut();
+    ensure!(state.contains_token(&params.token_id), ContractError::InvalidTokenId);
+
+    state.settle_dividends(&params.token_id, &holder);
+    let amount = state.dividend_accounts.get_mut(&(holder, params.token_id)).map_or(
+        Amount::zero(),
+        |mut account| {
+            let owed = account.owed;
+            account.owed = Amount::zero();
+            owed
+        },
+    );
+    ensure!(amount > Amount::zero(), CustomContractError::NothingToClaim.into());
+
+    logger.log(&Event::DividendClaimed(DividendClaimedEvent {
+        token_id: params.token_id,
+        amount,
+        holder,
+    }))?;
+
+    send_ccd(ctx, host, params.receiver, &params.data, amount)
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct DividendQuery {
+    pub token_id: ContractTokenId,
+    pub holder:   Address,
+}
+
+/// The dividends the holders can claim.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "claimableDividends",
+    parameter = "Vec<DividendQuery>",
+    return_value = "Vec<Amount>",
+    error = "ContractError"
+)]
+fn contract_claimable_dividends(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ContractResult<Vec<Amount>> {
+    let queries: Vec<DividendQuery> = ctx.parameter_cursor().get()?;
+    Ok(queries
+        .iter()
+        .map(|query| host.state().claimable_dividends(&query.token_id, &query.holder))
+        .collect())
+}
+{% endif %}
+
+{% if snapshots %}
+/// Take a snapshot of the balances and supplies, and return its ID. The
+/// values are recorded when they change after the snapshot.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "snapshot",
+    return_value = "u64",
+    error = "ContractError",
+    enable_logger,
+    mutable
+)]
+fn contract_snapshot(
+    ctx: &ReceiveContext,
+    host: &mut Host<State>,
+    logger: &mut impl HasLogger,
+) -> ContractResult<u64> {
+    let sender = ctx.sender();
+
+    {% if multisig %}
+    let multisig_call = check_multisig_call(ctx, host.state())?;
+    {% endif %}
+    {% if roles %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}host.state().has_role(&sender, Roles::ADMIN, ctx.metadata().slot_time()),
+        ContractError::Unauthorized
+    );
+    {% else %}
+    ensure!(
+        {% if multisig %}multisig_call || {% endif %}sender == host.state().owner,
+        ContractError::Unauthorized
+    );
+    {% endif %}
+
+    let state = host.state_mut();
+    state.snapshot_id += 1;
+    let snapshot_id = state.snapshot_id;
+
+    logger.log(&Event::Snapshot(SnapshotEvent {
+        snapshot_id,
+    }))?;
+
+    Ok(snapshot_id)
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct BalanceOfAtQuery {
+    pub owner:       Address,
+    pub token_id:    ContractTokenId,
+    pub snapshot_id: u64,
+}
+
+/// The balances of owners at snapshots.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "balanceOfAt",
+    parameter = "Vec<BalanceOfAtQuery>",
+    return_value = "Vec<ContractTokenAmount>",
+    error = "ContractError"
+)]
+fn contract_balance_of_at(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ContractResult<Vec<ContractTokenAmount>> {
+    let queries: Vec<BalanceOfAtQuery> = ctx.parameter_cursor().get()?;
+    queries
+        .iter()
+        .map(|query| host.state().balance_at(&query.token_id, &query.owner, query.snapshot_id))
+        .collect()
+}
+
+#[derive(Serialize, SchemaType)]
+pub struct TotalSupplyAtQuery {
+    pub token_id:    ContractTokenId,
+    pub snapshot_id: u64,
+}
+
+/// The supplies of tokens at snapshots.
+#[receive(
+    contract = "{{ contract_name }}",
+    name = "totalSupplyAt",
+    parameter = "Vec<TotalSupplyAtQuery>",
+    return_value = "Vec<ContractTokenAmount>",
+    error = "ContractError"
+)]
+fn contract_total_supply_at(
+    ctx: &ReceiveContext,
+    host: &Host<State>,
+) -> ContractResult<Vec<ContractTokenAmount>> {
+    let queries: Vec<TotalSupplyAtQuery> = ctx.parameter_cursor().get()?;
+    queries
+        .iter()
+        .map(|query| host.state().supply_at(&query.token_id, query.snapshot_id))
+        .collect()
+}
 {% endif %}
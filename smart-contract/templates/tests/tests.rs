@@ -1,9 +1,20 @@
-use mint_wizard::{ContractBalanceOfQueryParams, ContractBalanceOfQueryResponse, *};
+use mint_wizard::*;
 use concordium_cis2::*;
 use concordium_smart_contract_testing::*;
 use concordium_std::{
-    collections::BTreeMap, AccountSignatures, CredentialSignatures, HashSha2256,
-    SignatureEd25519, Timestamp,
+    collections::BTreeMap, HashSha2256,
+    {% if sponsored or multisig %}
+    AccountSignatures, CredentialSignatures, SignatureEd25519,
+    {% endif %}
+    {% if sponsored %}
+    PublicKeyEd25519, Reject,
+    {% endif %}
+    {% if roles or updates %}
+    Duration,
+    {% endif %}
+    {% if roles or updates or sponsored or multisig %}
+    Timestamp,
+    {% endif %}
 };
 use concordium_std_derive::*;
 
@@ -16,11 +27,15 @@ const BOB_ADDR: Address = Address::Account(BOB);
 {% if roles %}
 const UPGRADER: AccountAddress =
     account_address!("2xdTv8awN1BjgYEw8W1BVXVtiEwG2b29U8KoZQqJrDuEqddseE");
+{% if updates %}
 const UPGRADER_ADDR: Address = Address::Account(UPGRADER);
+{% endif %}
 const PAUSER: AccountAddress =
     account_address!("2yWkbp92JL9LYVmxgP1QfTDsJs9sMLAWJBYMy8md3SQz5ErzEd");
+{% if pausable %}
 const PAUSER_ADDR: Address = Address::Account(PAUSER);
 {% endif %}
+{% endif %}
 
 /// Token IDs.
 const TOKEN_0: ContractTokenId = TokenIdU8(2);
@@ -28,6 +43,9 @@ const TOKEN_1: ContractTokenId = TokenIdU8(42);
 
 const TOKEN_0_METADATA: &str = "https://some.example/token/2A";
 const TOKEN_1_METADATA: &str = "https://some.example/token/3F";
+{% if wrapped_ccd %}
+const WRAPPED_METADATA: &str = "https://some.example/token/wccd";
+{% endif %}
 
 /// Initial balance of the accounts.
 const ACC_INITIAL_BALANCE: Amount = Amount::from_ccd(10000);
@@ -35,7 +53,7 @@ const ACC_INITIAL_BALANCE: Amount = Amount::from_ccd(10000);
 /// A signer with one key.
 const SIGNER: Signer = Signer::with_one_key();
 
-{% if sponsored %}
+{% if sponsored or multisig %}
 /// Dummy signature used as placeholder.
 const DUMMY_SIGNATURE: SignatureEd25519 = signature_ed25519!("00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000");
 {% endif %}
@@ -70,7 +88,7 @@ fn test_minting() {
     let update = chain
         .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.mint".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.mint".to_string()),
             address:      contract_address,
             message:      OwnedParameter::from_serial(&mint_params).expect("Mint params"),
         })
@@ -80,7 +98,7 @@ fn test_minting() {
     let invoke = chain
         .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.view".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
             address:      contract_address,
             message:      OwnedParameter::empty(),
         })
@@ -88,7 +106,7 @@ fn test_minting() {
 
     // Check that the tokens are owned by Alice.
     let rv: ViewState = invoke.parse_return_value().expect("ViewState return value");
-    assert_eq!(rv.tokens[..], [TOKEN_0, TOKEN_1]);
+    assert_eq!(rv.tokens[..], [{% if wrapped_ccd %}WRAPPED_TOKEN_ID, {% endif %}TOKEN_0, TOKEN_1]);
     assert_eq!(rv.state, vec![(ALICE_ADDR, ViewAddressState {
         balances:  vec![(TOKEN_0, 100.into()), (TOKEN_1, 200.into())],
         operators: Vec::new(),
@@ -115,8 +133,258 @@ fn test_minting() {
         }),
     ]);
 }
+
+/// Test that minting can be finalized per token and for all tokens.
+#[test]
+fn test_finalize_minting() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let update = finalize_minting(&mut chain, contract_address, Some(TOKEN_1))
+        .expect("Finalize minting of TOKEN_1");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::MintingFinalized(MintingFinalizedEvent {
+        token_id: Some(TOKEN_1),
+    })]);
+
+    let update = mint_as(&mut chain, contract_address, ALICE, TOKEN_1, 1)
+        .expect_err("Mint a finalized token");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MintingFinalized));
+
+    let update = finalize_minting(&mut chain, contract_address, Some(TOKEN_1))
+        .expect_err("Finalize minting of TOKEN_1 twice");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MintingFinalized));
+
+    mint_as(&mut chain, contract_address, ALICE, TOKEN_0, 1).expect("Mint TOKEN_0");
+
+    finalize_minting(&mut chain, contract_address, None).expect("Finalize minting");
+
+    let update = mint_as(&mut chain, contract_address, ALICE, TOKEN_0, 1)
+        .expect_err("Mint after finalizing");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MintingFinalized));
+
+    let invoke = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke view");
+    let rv: ViewState = invoke.parse_return_value().expect("ViewState return value");
+    assert!(rv.minting_finalized);
+    assert_eq!(rv.finalized_tokens, [TOKEN_1]);
+}
+
+/// Test updating and locking the max supply of a token.
+#[test]
+fn test_update_max_supply() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let params = UpdateMaxSupplyParams {
+        token_id:   TOKEN_1,
+        max_supply: TokenAmountU64(150),
+    };
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "updateMaxSupply",
+        OwnedParameter::from_serial(&params).expect("UpdateMaxSupply params"),
+    )
+    .expect("Lower the max supply");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::MaxSupplyUpdated(MaxSupplyUpdatedEvent {
+        token_id:   TOKEN_1,
+        max_supply: TokenAmountU64(150),
+    })]);
+
+    let update = mint_as(&mut chain, contract_address, ALICE, TOKEN_1, 51)
+        .expect_err("Mint above the lowered max supply");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MaxSupplyReached));
+    mint_as(&mut chain, contract_address, ALICE, TOKEN_1, 50).expect("Mint up to the max supply");
+
+    let params = UpdateMaxSupplyParams {
+        token_id:   TOKEN_1,
+        max_supply: TokenAmountU64(149),
+    };
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "updateMaxSupply",
+        OwnedParameter::from_serial(&params).expect("UpdateMaxSupply params"),
+    )
+    .expect_err("Lower the max supply below the circulating supply");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MaxSupplyBelowSupply));
+
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "lockMaxSupply",
+        OwnedParameter::from_serial(&TOKEN_1).expect("Token ID"),
+    )
+    .expect("Lock the max supply");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::MaxSupplyLocked(MaxSupplyLockedEvent {
+        token_id: TOKEN_1,
+    })]);
+
+    let params = UpdateMaxSupplyParams {
+        token_id:   TOKEN_1,
+        max_supply: TokenAmountU64(1000),
+    };
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "updateMaxSupply",
+        OwnedParameter::from_serial(&params).expect("UpdateMaxSupply params"),
+    )
+    .expect_err("Update a locked max supply");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MaxSupplyLocked));
+}
+
+/// Test that `mintNext` allocates the token IDs that are not in use yet.
+#[test]
+fn test_mint_next() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let token = |url: &str| {
+        (
+            MetadataUrl {
+                url:  url.to_string(),
+                hash: None,
+            },
+            TokenParams {
+                amount:     TokenAmountU64(1),
+                max_supply: TokenAmountU64(1),
+            },
+        )
+    };
+    let params = MintNextParams {
+        owner:  BOB_ADDR,
+        tokens: vec![token("https://some.example/token/0"), token("https://some.example/token/1"), token("https://some.example/token/3")],
+    };
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "mintNext",
+        OwnedParameter::from_serial(&params).expect("MintNext params"),
+    )
+    .expect("Mint next tokens");
+    // TOKEN_0 already uses token ID 2{% if wrapped_ccd %} and the wrapped CCD token ID 0{% endif %}.
+    let token_ids: Vec<ContractTokenId> = update.parse_return_value().expect("Token IDs");
+    {% if wrapped_ccd %}
+    assert_eq!(token_ids, [TokenIdU8(1), TokenIdU8(3), TokenIdU8(4)]);
+    {% else %}
+    assert_eq!(token_ids, [TokenIdU8(0), TokenIdU8(1), TokenIdU8(3)]);
+    {% endif %}
+
+    let events: Vec<Cis2Event<ContractTokenId, ContractTokenAmount>> = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect();
+    assert!(events.contains(&Cis2Event::Mint(MintEvent {
+        token_id: token_ids[2],
+        amount:   TokenAmountU64(1),
+        owner:    BOB_ADDR,
+    })));
+    assert!(events.contains(&Cis2Event::TokenMetadata(TokenMetadataEvent {
+        token_id:     token_ids[2],
+        metadata_url: token("https://some.example/token/3").0,
+    })));
+
+    let params = MintNextParams {
+        owner:  BOB_ADDR,
+        tokens: vec![token("https://some.example/token/4")],
+    };
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "mintNext",
+        OwnedParameter::from_serial(&params).expect("MintNext params"),
+    )
+    .expect("Mint next token");
+    let token_ids: Vec<ContractTokenId> = update.parse_return_value().expect("Token IDs");
+    assert_eq!(token_ids, [TokenIdU8({% if wrapped_ccd %}5{% else %}4{% endif %})]);
+}
+
+/// Finalize minting as Alice.
+fn finalize_minting(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    token_id: Option<ContractTokenId>,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    alice_update(
+        chain,
+        contract_address,
+        "finalizeMinting",
+        OwnedParameter::from_serial(&token_id).expect("Token ID"),
+    )
+}
+
+/// Mint an amount of a token to the sender.
+fn mint_as(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    sender: AccountAddress,
+    token_id: ContractTokenId,
+    amount: u64,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    let mut tokens = BTreeMap::new();
+    tokens.insert(token_id, (
+        MetadataUrl {
+            url:  TOKEN_1_METADATA.to_string(),
+            hash: None,
+        },
+        TokenParams {
+            amount:     TokenAmountU64(amount),
+            max_supply: TokenAmountU64(1000),
+        },
+    ));
+    let mint_params = MintParams {
+        owner: Address::Account(sender),
+        tokens,
+    };
+    chain.contract_update(SIGNER, sender, Address::Account(sender), Energy::from(10000), UpdateContractPayload {
+        amount:       Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.mint".to_string()),
+        address:      contract_address,
+        message:      OwnedParameter::from_serial(&mint_params).expect("Mint params"),
+    })
+}
 {% endif %}
 
+/// Call an entrypoint of the contract as Alice, who is the owner and admin.
+fn alice_update(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    entrypoint: &str,
+    message: OwnedParameter,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+        amount:       Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked(format!("{{ contract_name }}.{}", entrypoint)),
+        address:      contract_address,
+        message,
+    })
+}
+
 /// Test regular transfer where sender is the owner.
 #[test]
 fn test_account_transfer() {
@@ -135,7 +403,7 @@ fn test_account_transfer() {
     let update = chain
         .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.transfer".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.transfer".to_string()),
             address:      contract_address,
             message:      OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
         })
@@ -146,7 +414,7 @@ fn test_account_transfer() {
     let invoke = chain
         .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.view".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
             address:      contract_address,
             message:      OwnedParameter::empty(),
         })
@@ -194,7 +462,7 @@ fn test_add_operator() {
     let update = chain
         .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.updateOperator".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.updateOperator".to_string()),
             address:      contract_address,
             message:      OwnedParameter::from_serial(&params).expect("UpdateOperator params"),
         })
@@ -224,7 +492,7 @@ fn test_add_operator() {
     let invoke = chain
         .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.operatorOf".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.operatorOf".to_string()),
             address:      contract_address,
             message:      OwnedParameter::from_serial(&query_params).expect("OperatorOf params"),
         })
@@ -256,7 +524,7 @@ fn test_unauthorized_sender() {
     let update = chain
         .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.transfer".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.transfer".to_string()),
             address:      contract_address,
             message:      OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
         })
@@ -281,7 +549,7 @@ fn test_operator_can_transfer() {
     chain
         .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.updateOperator".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.updateOperator".to_string()),
             address:      contract_address,
             message:      OwnedParameter::from_serial(&params).expect("UpdateOperator params"),
         })
@@ -299,7 +567,7 @@ fn test_operator_can_transfer() {
     chain
         .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.transfer".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.transfer".to_string()),
             address:      contract_address,
             message:      OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
         })
@@ -310,7 +578,7 @@ fn test_operator_can_transfer() {
     let invoke = chain
         .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.view".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
             address:      contract_address,
             message:      OwnedParameter::empty(),
         })
@@ -328,177 +596,568 @@ fn test_operator_can_transfer() {
     ]);
 }
 
-{% if sponsored %}
-{% if mintable %}
-/// Test permit mint function. The signature is generated in the test
-/// case. ALICE mints tokens to her account.
+/// Test that the init parameter can hand the preminted tokens and the
+/// contract to other addresses than the account initializing it.
 #[test]
-fn test_permit_mint() {
-    let (mut chain, keypairs, contract_address, _module_reference) =
-        initialize_chain_and_contract();
-
-    // Check balances in state.
-    let balance_of_alice_and_bob = get_balances(&chain, contract_address);
+fn test_init_distribution() {
+    let mut chain = Chain::new();
+    chain.create_account(Account::new(ALICE, ACC_INITIAL_BALANCE));
+    chain.create_account(Account::new(BOB, ACC_INITIAL_BALANCE));
 
-    assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(100), TokenAmountU64(0)]);
+    let module = module_load_v1("dist/module.wasm.v1").expect("Module exists");
+    let deployment = chain.module_deploy_v1(SIGNER, ALICE, module).expect("Deploy valid module");
 
-    let token_params = TokenParams {
-        amount: TokenAmountU64(100),
-        max_supply: TokenAmountU64(1000),
+    let metadata_url = MetadataUrl {
+        url:  TOKEN_0_METADATA.to_string(),
+        hash: None,
     };
-
-    let mut mint_tokens = BTreeMap::new();
-    mint_tokens.insert(TOKEN_1, (
-        MetadataUrl {
-            url:  TOKEN_1_METADATA.to_string(),
-            hash: None,
-        }, token_params
-    ));
-
-    // Create input parameters for the `mint` function.
-    let payload = MintParams {
-        owner:      ALICE_ADDR,
-        tokens:     mint_tokens,
+    // Premint 100 tokens, of which Alice receives the given amount.
+    let init_params = |alice_amount: u64| {
+        let mut premint_tokens = BTreeMap::new();
+        premint_tokens.insert(TOKEN_0, (metadata_url.clone(), TokenParams {
+            amount:     TokenAmountU64(100),
+            max_supply: TokenAmountU64(1000),
+        }));
+        InitParams {
+            premint_tokens,
+            premint_recipients: BTreeMap::from([(TOKEN_0, vec![(
+                ALICE_ADDR,
+                TokenAmountU64(alice_amount),
+            )])]),
+            owner: Some(BOB_ADDR),
+            metadata_base: None,
+            {% if roles %}
+            roles: vec![(ALICE_ADDR, Roles::ADMIN)],
+            {% endif %}
+            {% if wrapped_ccd %}
+            wrapped_metadata: wrapped_metadata(),
+            {% endif %}
+        }
     };
 
-    let update =
-        permit(&mut chain, contract_address, to_bytes(&payload), "mint".to_string(), keypairs);
+    // Recipients can not receive more than the preminted amount.
+    chain
+        .contract_init(SIGNER, ALICE, Energy::from(10000), InitContractPayload {
+            amount:    Amount::zero(),
+            mod_ref:   deployment.module_reference,
+            init_name: OwnedContractName::new_unchecked("init_{{ contract_name }}".to_string()),
+            param:     OwnedParameter::from_serial(&init_params(101)).expect("Init params"),
+        })
+        .expect_err("Initialize with too large premint recipients");
 
-    // Check that the correct events occurred.
-    let events = update
-        .events()
-        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
-        .collect::<Vec<Event>>();
+    let init = chain
+        .contract_init(SIGNER, ALICE, Energy::from(10000), InitContractPayload {
+            amount:    Amount::zero(),
+            mod_ref:   deployment.module_reference,
+            init_name: OwnedContractName::new_unchecked("init_{{ contract_name }}".to_string()),
+            param:     OwnedParameter::from_serial(&init_params(30)).expect("Init params"),
+        })
+        .expect("Initialize contract");
 
+    let events = init
+        .events
+        .iter()
+        .map(|e| e.parse().expect("Deserialize event"))
+        .collect::<Vec<Event>>();
     assert_eq!(events, [
+        {% if wrapped_ccd %}
+        Event::Cis2Event(Cis2Event::TokenMetadata(TokenMetadataEvent {
+            token_id:     WRAPPED_TOKEN_ID,
+            metadata_url: wrapped_metadata(),
+        })),
+        {% endif %}
+        {% if roles %}
+        Event::GrantRole(GrantRoleEvent {
+            address: BOB_ADDR,
+            role:    Roles::ADMIN,
+            expiry:  None,
+        }),
+        Event::GrantRole(GrantRoleEvent {
+            address: ALICE_ADDR,
+            role:    Roles::ADMIN,
+            expiry:  None,
+        }),
+        {% endif %}
         Event::Cis2Event(Cis2Event::Mint(MintEvent {
-            token_id: TOKEN_1,
-            amount:   TokenAmountU64(100),
+            token_id: TOKEN_0,
+            amount:   TokenAmountU64(30),
             owner:    ALICE_ADDR,
         })),
+        Event::Cis2Event(Cis2Event::Mint(MintEvent {
+            token_id: TOKEN_0,
+            amount:   TokenAmountU64(70),
+            owner:    BOB_ADDR,
+        })),
         Event::Cis2Event(Cis2Event::TokenMetadata(TokenMetadataEvent {
-            token_id:     TOKEN_1,
-            metadata_url: MetadataUrl {
-                url:  "https://some.example/token/3F".to_string(),
-                hash: None,
-            },
+            token_id: TOKEN_0,
+            metadata_url,
         })),
-        Event::Nonce(NonceEvent {
-            account: ALICE,
-            nonce:   0,
-        })
     ]);
 
-    // Check balances in state.
-    let balance_of_alice_and_bob = get_balances(&chain, contract_address);
-
-    assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(200), TokenAmountU64(0)]);
+    let invoke = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
+            address:      init.contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke view");
+    let rv: ViewState = invoke.parse_return_value().expect("ViewState return value");
+    assert_eq!(rv.owner, BOB_ADDR);
+    assert_eq!(rv.state, vec![
+        (ALICE_ADDR, ViewAddressState {
+            balances:  vec![(TOKEN_0, 30.into())],
+            operators: Vec::new(),
+        }),
+        (BOB_ADDR, ViewAddressState {
+            balances:  vec![(TOKEN_0, 70.into())],
+            operators: Vec::new(),
+        }),
+    ]);
 }
-{% endif %}
 
-{% if burnable %}
-/// Test permit burn function. The signature is generated in the test
-/// case. ALICE burns tokens from her account.
+{% if not wrapped_ccd %}
+/// Test that clients sending only `premint_tokens` can initialize the
+/// contract, which is then owned by the account initializing it.
 #[test]
-fn test_permit_burn() {
-    let (mut chain, keypairs, contract_address, _module_reference) =
-        initialize_chain_and_contract();
+fn test_init_premint_tokens_only() {
+    let mut chain = Chain::new();
+    chain.create_account(Account::new(ALICE, ACC_INITIAL_BALANCE));
 
-    // Check balances in state.
-    let balance_of_alice_and_bob = get_balances(&chain, contract_address);
+    let module = module_load_v1("dist/module.wasm.v1").expect("Module exists");
+    let deployment = chain.module_deploy_v1(SIGNER, ALICE, module).expect("Deploy valid module");
 
-    assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(100), TokenAmountU64(0)]);
+    let mut premint_tokens = BTreeMap::new();
+    premint_tokens.insert(TOKEN_0, (
+        MetadataUrl {
+            url:  TOKEN_0_METADATA.to_string(),
+            hash: None,
+        },
+        TokenParams {
+            amount:     TokenAmountU64(100),
+            max_supply: TokenAmountU64(1000),
+        },
+    ));
+    let init = chain
+        .contract_init(SIGNER, ALICE, Energy::from(10000), InitContractPayload {
+            amount:    Amount::zero(),
+            mod_ref:   deployment.module_reference,
+            init_name: OwnedContractName::new_unchecked("init_{{ contract_name }}".to_string()),
+            param:     OwnedParameter::from_serial(&premint_tokens).expect("Init params"),
+        })
+        .expect("Initialize contract with premint tokens only");
 
-    // Create input parameters for the `burn` function.
-    let payload = BurnParams {
-        owner:    ALICE_ADDR,
-        amount:   TokenAmountU64(1),
-        token_id: TOKEN_1,
-    };
+    let rv: ViewState = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
+            address:      init.contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke view")
+        .parse_return_value()
+        .expect("ViewState return value");
+    assert_eq!(rv.owner, ALICE_ADDR);
+    assert_eq!(rv.state, vec![(ALICE_ADDR, ViewAddressState {
+        balances:  vec![(TOKEN_0, 100.into())],
+        operators: Vec::new(),
+    })]);
+}
 
-    let update =
-        permit(&mut chain, contract_address, to_bytes(&payload), "burn".to_string(), keypairs);
+/// Test that the contract can be initialized without `metadata_base`, leaving
+/// it unset.
+#[test]
+fn test_init_without_metadata_base() {
+    let mut chain = Chain::new();
+    chain.create_account(Account::new(ALICE, ACC_INITIAL_BALANCE));
 
-    // Check that the correct events occurred.
+    let module = module_load_v1("dist/module.wasm.v1").expect("Module exists");
+    let deployment = chain.module_deploy_v1(SIGNER, ALICE, module).expect("Deploy valid module");
+
+    let mut premint_tokens = BTreeMap::new();
+    premint_tokens.insert(TOKEN_0, (
+        MetadataUrl {
+            url:  TOKEN_0_METADATA.to_string(),
+            hash: None,
+        },
+        TokenParams {
+            amount:     TokenAmountU64(100),
+            max_supply: TokenAmountU64(1000),
+        },
+    ));
+    let premint_recipients: BTreeMap<ContractTokenId, Vec<(Address, ContractTokenAmount)>> =
+        BTreeMap::new();
+    let params = (premint_tokens, premint_recipients, Some(BOB_ADDR));
+    let init = chain
+        .contract_init(SIGNER, ALICE, Energy::from(10000), InitContractPayload {
+            amount:    Amount::zero(),
+            mod_ref:   deployment.module_reference,
+            init_name: OwnedContractName::new_unchecked("init_{{ contract_name }}".to_string()),
+            param:     OwnedParameter::from_serial(&params).expect("Init params"),
+        })
+        .expect("Initialize contract without metadata base");
+
+    let rv: ViewState = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
+            address:      init.contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke view")
+        .parse_return_value()
+        .expect("ViewState return value");
+    assert_eq!(rv.owner, BOB_ADDR);
+    assert!(rv.metadata_base.is_none());
+}
+{% endif %}
+
+/// Test that tokens without a metadata URL get it from the base URL, unless
+/// it is overridden.
+#[test]
+fn test_metadata_base() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let metadata_base = MetadataBase {
+        url:    "https://some.example/collection/".to_string(),
+        suffix: ".json".to_string(),
+    };
+    let update = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.setMetadataBase".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&metadata_base).expect("MetadataBase"),
+        })
+        .expect("Set metadata base");
     let events = update
         .events()
         .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
         .collect::<Vec<Event>>();
-
-    assert_eq!(events, [
-        Event::Cis2Event(Cis2Event::Burn(BurnEvent {
-            token_id: TOKEN_1,
-            amount:   TokenAmountU64(1),
-            owner:    ALICE_ADDR,
-        })),
-        Event::Nonce(NonceEvent {
-            account: ALICE,
-            nonce:   0,
+    assert_eq!(events, [Event::MetadataBaseUpdated(metadata_base)]);
+
+    // Make TOKEN_0 use the base URL.
+    let hash = Some([1u8; 32]);
+    let params = SetTokenMetadataParams {
+        token_id:     TOKEN_0,
+        metadata_url: MetadataUrl {
+            url: String::new(),
+            hash,
+        },
+    };
+    let update = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.setTokenMetadata".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("SetTokenMetadata params"),
         })
-    ]);
-
-    // Check balances in state.
-    let balance_of_alice_and_bob = get_balances(&chain, contract_address);
+        .expect("Set token metadata");
+    let base_metadata_url = MetadataUrl {
+        url: "https://some.example/collection/02.json".to_string(),
+        hash,
+    };
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::Cis2Event(Cis2Event::TokenMetadata(TokenMetadataEvent {
+        token_id:     TOKEN_0,
+        metadata_url: base_metadata_url.clone(),
+    }))]);
 
-    assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(99), TokenAmountU64(0)]);
+    let query = TokenMetadataQueryParams::<ContractTokenId> {
+        queries: vec![TOKEN_0, TOKEN_1],
+    };
+    let invoke = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.tokenMetadata".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&query).expect("TokenMetadata params"),
+        })
+        .expect("Invoke tokenMetadata");
+    let rv: TokenMetadataQueryResponse = invoke.parse_return_value().expect("TokenMetadata return value");
+    assert_eq!(rv.0, [base_metadata_url, MetadataUrl {
+        url:  TOKEN_1_METADATA.to_string(),
+        hash: None,
+    }]);
 }
-{% endif %}
 
-/// Test permit update operator function. The signature is generated in the test
-/// case. ALICE adds BOB as an operator.
+/// Test that tokens show the placeholder until the committed metadata base is
+/// revealed.
 #[test]
-fn test_permit_update_operator() {
-    let (mut chain, keypairs, contract_address, _module_reference) =
+fn test_delayed_reveal() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
         initialize_chain_and_contract();
 
-    // Check operator in state
-    let bob_is_operator_of_alice = operator_of(&chain, contract_address);
+    let metadata_base = MetadataBase {
+        url:    "https://some.example/revealed/".to_string(),
+        suffix: ".json".to_string(),
+    };
+    let commitment: HashSha2256 = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.viewRevealCommitment".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&metadata_base).expect("MetadataBase"),
+        })
+        .expect("Invoke viewRevealCommitment")
+        .parse_return_value()
+        .expect("Commitment return value");
 
-    assert_eq!(bob_is_operator_of_alice, OperatorOfQueryResponse(vec![false]));
+    let pending_reveal = PendingReveal {
+        placeholder: MetadataUrl {
+            url:  "https://some.example/placeholder.json".to_string(),
+            hash: None,
+        },
+        commitment:  Some(commitment),
+    };
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "prepareReveal",
+        OwnedParameter::from_serial(&pending_reveal).expect("PendingReveal"),
+    )
+    .expect("Prepare reveal");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::RevealPrepared(pending_reveal.clone())]);
 
-    // Create input parameters for the `permit` updateOperator function.
-    let update_operator = UpdateOperator {
-        update:   OperatorUpdate::Add,
-        operator: BOB_ADDR,
+    // A prepared reveal can not be replaced.
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "prepareReveal",
+        OwnedParameter::from_serial(&pending_reveal).expect("PendingReveal"),
+    )
+    .expect_err("Prepare reveal twice");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::RevealAlreadyPrepared));
+
+    // The metadata base can not be set around the commitment.
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "setMetadataBase",
+        OwnedParameter::from_serial(&metadata_base).expect("MetadataBase"),
+    )
+    .expect_err("Set metadata base with a prepared reveal");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::RevealAlreadyPrepared));
+
+    // Hide TOKEN_0 behind the placeholder.
+    let params = SetTokenMetadataParams {
+        token_id:     TOKEN_0,
+        metadata_url: MetadataUrl {
+            url:  String::new(),
+            hash: None,
+        },
     };
-    let payload = UpdateOperatorParams(vec![update_operator]);
+    alice_update(
+        &mut chain,
+        contract_address,
+        "setTokenMetadata",
+        OwnedParameter::from_serial(&params).expect("SetTokenMetadata params"),
+    )
+    .expect("Set token metadata");
 
-    let update = permit(
+    let query = TokenMetadataQueryParams::<ContractTokenId> {
+        queries: vec![TOKEN_0],
+    };
+    let invoke = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.tokenMetadata".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&query).expect("TokenMetadata params"),
+        })
+        .expect("Invoke tokenMetadata");
+    let rv: TokenMetadataQueryResponse = invoke.parse_return_value().expect("TokenMetadata return value");
+    assert_eq!(rv.0, std::slice::from_ref(&pending_reveal.placeholder));
+
+    // Revealing another metadata base than committed to fails.
+    let other_base = MetadataBase {
+        url:    "https://some.example/other/".to_string(),
+        suffix: ".json".to_string(),
+    };
+    let update = alice_update(
         &mut chain,
         contract_address,
-        to_bytes(&payload),
-        "updateOperator".to_string(),
-        keypairs,
-    );
+        "reveal",
+        OwnedParameter::from_serial(&other_base).expect("MetadataBase"),
+    )
+    .expect_err("Reveal other base");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::RevealCommitmentMismatch));
 
-    // Check that the correct events occurred.
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "reveal",
+        OwnedParameter::from_serial(&metadata_base).expect("MetadataBase"),
+    )
+    .expect("Reveal");
     let events = update
         .events()
         .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
         .collect::<Vec<Event>>();
-
     assert_eq!(events, [
-        Event::Cis2Event(Cis2Event::UpdateOperator(UpdateOperatorEvent {
-            update:   OperatorUpdate::Add,
-            owner:    ALICE_ADDR,
-            operator: BOB_ADDR,
+        Event::MetadataBaseUpdated(metadata_base),
+        Event::Cis2Event(Cis2Event::TokenMetadata(TokenMetadataEvent {
+            token_id:     TOKEN_0,
+            metadata_url: MetadataUrl {
+                url:  "https://some.example/revealed/02.json".to_string(),
+                hash: None,
+            },
         })),
-        Event::Nonce(NonceEvent {
-            account: ALICE,
-            nonce:   0,
-        })
     ]);
 
-    // Check operator in state
-    let bob_is_operator_of_alice = operator_of(&chain, contract_address);
+    // The reveal can only happen once.
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "reveal",
+        OwnedParameter::from_serial(&other_base).expect("MetadataBase"),
+    )
+    .expect_err("Reveal twice");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::NoPendingReveal));
 
-    assert_eq!(bob_is_operator_of_alice, OperatorOfQueryResponse(vec![true]));
+    // The revealed metadata base can not be changed.
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "setMetadataBase",
+        OwnedParameter::from_serial(&other_base).expect("MetadataBase"),
+    )
+    .expect_err("Set metadata base after the reveal");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MetadataRevealed));
+
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "prepareReveal",
+        OwnedParameter::from_serial(&pending_reveal).expect("PendingReveal"),
+    )
+    .expect_err("Prepare reveal after the reveal");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MetadataRevealed));
 }
 
-/// Test permit transfer function. The signature is generated in the test case.
-/// TOKEN_1 is transferred from Alice to Bob.
+/// Test setting, removing and querying on-chain attributes of a token.
 #[test]
-fn test_permit_transfer() {
+fn test_token_attributes() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    {% if roles %}
+    // Grant ATTRIBUTEUPDATER to Alice, being ADMIN is not enough.
+    let grant_role_params = GrantRoleParams {
+        address: ALICE_ADDR,
+        role:    Roles::ATTRIBUTEUPDATER,
+        expiry:  None,
+    };
+    alice_update(
+        &mut chain,
+        contract_address,
+        "grantRole",
+        OwnedParameter::from_serial(&grant_role_params).expect("GrantRole params"),
+    )
+    .expect("Grant ATTRIBUTEUPDATER to Alice");
+    {% endif %}
+
+    let params = SetAttributesParams {
+        token_id:         TOKEN_0,
+        attributes:       vec![
+            ("level".to_string(), Some("1".to_string())),
+            ("durability".to_string(), Some("100".to_string())),
+        ],
+        refresh_metadata: false,
+    };
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "setAttributes",
+        OwnedParameter::from_serial(&params).expect("SetAttributes params"),
+    )
+    .expect("Set attributes");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::AttributesUpdated(params)]);
+
+    // Update one attribute, remove the other and refresh the metadata.
+    let params = SetAttributesParams {
+        token_id:         TOKEN_0,
+        attributes:       vec![
+            ("level".to_string(), Some("2".to_string())),
+            ("durability".to_string(), None),
+        ],
+        refresh_metadata: true,
+    };
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "setAttributes",
+        OwnedParameter::from_serial(&params).expect("SetAttributes params"),
+    )
+    .expect("Update attributes");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [
+        Event::Cis2Event(Cis2Event::TokenMetadata(TokenMetadataEvent {
+            token_id:     TOKEN_0,
+            metadata_url: MetadataUrl {
+                url:  TOKEN_0_METADATA.to_string(),
+                hash: None,
+            },
+        })),
+        Event::AttributesUpdated(params),
+    ]);
+
+    let invoke = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.tokenAttributes".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&vec![TOKEN_0, TOKEN_1]).expect("TokenAttributes params"),
+        })
+        .expect("Invoke tokenAttributes");
+    let rv: TokenAttributesQueryResponse = invoke.parse_return_value().expect("TokenAttributes return value");
+    assert_eq!(rv, [vec![("level".to_string(), "2".to_string())], Vec::new()]);
+
+    // Bob can not set attributes.
+    let params = SetAttributesParams {
+        token_id:         TOKEN_1,
+        attributes:       vec![("level".to_string(), Some("99".to_string()))],
+        refresh_metadata: false,
+    };
+    let update = chain
+        .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.setAttributes".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("SetAttributes params"),
+        })
+        .expect_err("Set attributes as Bob");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Unauthorized);
+}
+
+{% if sponsored %}
+{% if mintable %}
+/// Test permit mint function. The signature is generated in the test
+/// case. ALICE mints tokens to her account.
+#[test]
+fn test_permit_mint() {
     let (mut chain, keypairs, contract_address, _module_reference) =
         initialize_chain_and_contract();
 
@@ -507,18 +1166,27 @@ fn test_permit_transfer() {
 
     assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(100), TokenAmountU64(0)]);
 
-    // Create input parameters for the `permit` transfer function.
-    let transfer = concordium_cis2::Transfer {
-        from:     ALICE_ADDR,
-        to:       Receiver::from_account(BOB),
-        token_id: TOKEN_1,
-        amount:   ContractTokenAmount::from(1),
-        data:     AdditionalData::empty(),
+    let token_params = TokenParams {
+        amount: TokenAmountU64(100),
+        max_supply: TokenAmountU64(1000),
+    };
+
+    let mut mint_tokens = BTreeMap::new();
+    mint_tokens.insert(TOKEN_1, (
+        MetadataUrl {
+            url:  TOKEN_1_METADATA.to_string(),
+            hash: None,
+        }, token_params
+    ));
+
+    // Create input parameters for the `mint` function.
+    let payload = MintParams {
+        owner:      ALICE_ADDR,
+        tokens:     mint_tokens,
     };
-    let payload = TransferParams::from(vec![transfer]);
 
     let update =
-        permit(&mut chain, contract_address, to_bytes(&payload), "transfer".to_string(), keypairs);
+        permit(&mut chain, contract_address, to_bytes(&payload), "mint".to_string(), keypairs);
 
     // Check that the correct events occurred.
     let events = update
@@ -527,11 +1195,17 @@ fn test_permit_transfer() {
         .collect::<Vec<Event>>();
 
     assert_eq!(events, [
-        Event::Cis2Event(Cis2Event::Transfer(TransferEvent {
+        Event::Cis2Event(Cis2Event::Mint(MintEvent {
             token_id: TOKEN_1,
-            amount:   ContractTokenAmount::from(1),
-            from:     ALICE_ADDR,
-            to:       BOB_ADDR,
+            amount:   TokenAmountU64(100),
+            owner:    ALICE_ADDR,
+        })),
+        Event::Cis2Event(Cis2Event::TokenMetadata(TokenMetadataEvent {
+            token_id:     TOKEN_1,
+            metadata_url: MetadataUrl {
+                url:  "https://some.example/token/3F".to_string(),
+                hash: None,
+            },
         })),
         Event::Nonce(NonceEvent {
             account: ALICE,
@@ -542,52 +1216,50 @@ fn test_permit_transfer() {
     // Check balances in state.
     let balance_of_alice_and_bob = get_balances(&chain, contract_address);
 
-    assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(99), TokenAmountU64(1)]);
+    assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(200), TokenAmountU64(0)]);
 }
 {% endif %}
 
 {% if burnable %}
-/// Test burning tokens.
+/// Test permit burn function. The signature is generated in the test
+/// case. ALICE burns tokens from her account.
 #[test]
-fn test_burning_tokens() {
-    let (mut chain, _keypairs, contract_address, _module_reference) =
+fn test_permit_burn() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
         initialize_chain_and_contract();
 
-    // Create input parameters to burn one of Alice's tokens.
-    let burn_params = BurnParams {
+    // Check balances in state.
+    let balance_of_alice_and_bob = get_balances(&chain, contract_address);
+
+    assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(100), TokenAmountU64(0)]);
+
+    // Create input parameters for the `burn` function.
+    let payload = BurnParams {
         owner:    ALICE_ADDR,
         amount:   TokenAmountU64(1),
         token_id: TOKEN_1,
     };
 
-    // Burn one of Alice's tokens.
-    let update = chain
-        .contract_update(
-            Signer::with_one_key(),
-            ALICE,
-            ALICE_ADDR,
-            Energy::from(10000),
-            UpdateContractPayload {
-                amount:       Amount::zero(),
-                address:      contract_address,
-                receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.burn".to_string()),
-                message:      OwnedParameter::from_serial(&burn_params)
-                    .expect("Should be a valid inut parameter"),
-            },
-        )
-        .expect("Should be able to burn tokens");
-
-    // Check that the event is logged.
-    let events = update.events().flat_map(|(_addr, events)| events);
+    let update =
+        permit(&mut chain, contract_address, to_bytes(&payload), "burn".to_string(), keypairs);
 
-    let events: Vec<Cis2Event<ContractTokenId, ContractTokenAmount>> =
-        events.map(|e| e.parse().expect("Deserialize event")).collect();
+    // Check that the correct events occurred.
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
 
-    assert_eq!(events, [Cis2Event::Burn(BurnEvent {
-        owner:    ALICE_ADDR,
-        amount:   TokenAmountU64(1),
-        token_id: TOKEN_1,
-    })]);
+    assert_eq!(events, [
+        Event::Cis2Event(Cis2Event::Burn(BurnEvent {
+            token_id: TOKEN_1,
+            amount:   TokenAmountU64(1),
+            owner:    ALICE_ADDR,
+        })),
+        Event::Nonce(NonceEvent {
+            account: ALICE,
+            nonce:   0,
+        })
+    ]);
 
     // Check balances in state.
     let balance_of_alice_and_bob = get_balances(&chain, contract_address);
@@ -596,268 +1268,3182 @@ fn test_burning_tokens() {
 }
 {% endif %}
 
-{% if updates %}
-/// Upgrade the contract to itself without invoking a migration function.
+/// Test permit update operator function. The signature is generated in the test
+/// case. ALICE adds BOB as an operator.
 #[test]
-fn test_upgrade_without_migration_function() {
-    let (mut chain, _keypairs, contract_address, module_reference) =
+fn test_permit_update_operator() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
         initialize_chain_and_contract();
 
-    let input_parameter = UpgradeParams {
-        module:  module_reference,
-        migrate: None,
+    // Check operator in state
+    let bob_is_operator_of_alice = operator_of(&chain, contract_address);
+
+    assert_eq!(bob_is_operator_of_alice, OperatorOfQueryResponse(vec![false]));
+
+    // Create input parameters for the `permit` updateOperator function.
+    let update_operator = UpdateOperator {
+        update:   OperatorUpdate::Add,
+        operator: BOB_ADDR,
     };
+    let payload = UpdateOperatorParams(vec![update_operator]);
 
-    // Upgrade `contract_version1` to `contract_version2`.
-    let update = chain.contract_update(
-        Signer::with_one_key(),
-        {% if roles %}
-        UPGRADER,
-        UPGRADER_ADDR,
-        {% else %}
-        ALICE,
-        ALICE_ADDR,
-        {% endif %}
-        Energy::from(10000),
-        UpdateContractPayload {
-            address:      contract_address,
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.upgrade".into()),
-            message:      OwnedParameter::from_serial(&input_parameter)
-                .expect("`UpgradeParams` should be a valid inut parameter"),
-            amount:       Amount::from_ccd(0),
-        },
+    let update = permit(
+        &mut chain,
+        contract_address,
+        to_bytes(&payload),
+        "updateOperator".to_string(),
+        keypairs,
     );
 
-    assert!(
-        !update.expect("Upgrade should succeed").state_changed,
-        "State should not be changed because no `migration` function was called"
-    );
+    // Check that the correct events occurred.
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+
+    assert_eq!(events, [
+        Event::Cis2Event(Cis2Event::UpdateOperator(UpdateOperatorEvent {
+            update:   OperatorUpdate::Add,
+            owner:    ALICE_ADDR,
+            operator: BOB_ADDR,
+        })),
+        Event::Nonce(NonceEvent {
+            account: ALICE,
+            nonce:   0,
+        })
+    ]);
+
+    // Check operator in state
+    let bob_is_operator_of_alice = operator_of(&chain, contract_address);
+
+    assert_eq!(bob_is_operator_of_alice, OperatorOfQueryResponse(vec![true]));
+}
+
+/// Test permit transfer function. The signature is generated in the test case.
+/// TOKEN_1 is transferred from Alice to Bob.
+#[test]
+fn test_permit_transfer() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    // Check balances in state.
+    let balance_of_alice_and_bob = get_balances(&chain, contract_address);
+
+    assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(100), TokenAmountU64(0)]);
+
+    // Create input parameters for the `permit` transfer function.
+    let transfer = concordium_cis2::Transfer {
+        from:     ALICE_ADDR,
+        to:       Receiver::from_account(BOB),
+        token_id: TOKEN_1,
+        amount:   ContractTokenAmount::from(1),
+        data:     AdditionalData::empty(),
+    };
+    let payload = TransferParams::from(vec![transfer]);
+
+    let update =
+        permit(&mut chain, contract_address, to_bytes(&payload), "transfer".to_string(), keypairs);
+
+    // Check that the correct events occurred.
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+
+    assert_eq!(events, [
+        Event::Cis2Event(Cis2Event::Transfer(TransferEvent {
+            token_id: TOKEN_1,
+            amount:   ContractTokenAmount::from(1),
+            from:     ALICE_ADDR,
+            to:       BOB_ADDR,
+        })),
+        Event::Nonce(NonceEvent {
+            account: ALICE,
+            nonce:   0,
+        })
+    ]);
+
+    // Check balances in state.
+    let balance_of_alice_and_bob = get_balances(&chain, contract_address);
+
+    assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(99), TokenAmountU64(1)]);
+}
+
+/// Test that `supportsPermit` reports the entrypoints of the enabled features.
+#[test]
+fn test_supports_permit() {
+    let (chain, _keypairs, contract_address, _module_reference) = initialize_chain_and_contract();
+
+    let entrypoints = [
+        "updateOperator",
+        "transfer",
+        "bumpNonce",
+        {% if mintable %}
+        "mint",
+        {% endif %}
+        {% if burnable %}
+        "burn",
+        {% endif %}
+        {% if roles %}
+        "grantRole",
+        "revokeRole",
+        {% endif %}
+        {% if pausable %}
+        "setPaused",
+        {% endif %}
+        {% if updates %}
+        "upgrade",
+        {% endif %}
+        {% if wrapped_ccd %}
+        "unwrap",
+        {% endif %}
+    ];
+    let mut queries: Vec<OwnedEntrypointName> =
+        entrypoints.iter().map(|e| OwnedEntrypointName::new_unchecked(e.to_string())).collect();
+    queries.push(OwnedEntrypointName::new_unchecked("setImplementors".to_string()));
 
-    // Invoke the view entrypoint and check that the state of the contract can be
-    // read.
     let invoke = chain
         .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.view".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.supportsPermit".to_string()),
             address:      contract_address,
-            message:      OwnedParameter::empty(),
+            message:      OwnedParameter::from_serial(&SupportsPermitQueryParams {
+                queries,
+            })
+            .expect("SupportsPermit params"),
         })
-        .expect("Invoke view");
+        .expect("Invoke supportsPermit");
+    let rv: SupportsQueryResponse = invoke.parse_return_value().expect("SupportsPermit return value");
 
-    // Check that the tokens (as set up in the
-    // `initialize_contract_with_alice_tokens` function) are owned by Alice.
-    let rv: ViewState = invoke.parse_return_value().expect("ViewState return value");
-    assert_eq!(rv.tokens[..], [TOKEN_0, TOKEN_1]);
-    assert_eq!(rv.state, vec![(ALICE_ADDR, ViewAddressState {
-        balances:  vec![(TOKEN_0, 100.into()), (TOKEN_1, 100.into())],
-        operators: Vec::new(),
-    })]);
+    let (supported, unsupported) = rv.results.split_at(entrypoints.len());
+    assert!(supported.iter().all(|result| matches!(result, SupportResult::Support)));
+    assert!(matches!(unsupported, [SupportResult::NoSupport]));
 }
-{% endif %}
 
-{% if pausable %}
-/// Test that the pause/unpause entrypoints correctly sets the pause value in
-/// the state.
+/// Test executing permits in a batch, skipping failed permits or failing the
+/// whole batch.
 #[test]
-fn test_pause_functionality() {
-    let (mut chain, _keypairs, contract_address, _module_reference) =
+fn test_permit_batch() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
         initialize_chain_and_contract();
 
-    // Pause the contract.
+    let transfer = concordium_cis2::Transfer {
+        from:     ALICE_ADDR,
+        to:       Receiver::from_account(BOB),
+        token_id: TOKEN_1,
+        amount:   ContractTokenAmount::from(1),
+        data:     AdditionalData::empty(),
+    };
+    let payload = to_bytes(&TransferParams::from(vec![transfer]));
+    let permit_batch = |chain: &mut Chain, params: &PermitBatchParams| {
+        chain.contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(50000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.permitBatch".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(params).expect("PermitBatch params"),
+        })
+    };
+
+    // The second permit reuses nonce 0 and fails, which fails an atomic batch.
+    let params = PermitBatchParams {
+        atomic:  true,
+        permits: vec![
+            signed_permit(&chain, contract_address, payload.clone(), "transfer".to_string(), 0, None, &keypairs),
+            signed_permit(&chain, contract_address, payload.clone(), "transfer".to_string(), 0, None, &keypairs),
+        ],
+    };
+    let update = permit_batch(&mut chain, &params).expect_err("Atomic batch with a failing permit");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::NonceMismatch));
+    assert_eq!(get_balances(&chain, contract_address).0, [TokenAmountU64(100), TokenAmountU64(0)]);
+
+    // A best-effort batch skips the failed permit.
+    let params = PermitBatchParams {
+        atomic:  false,
+        permits: vec![
+            signed_permit(&chain, contract_address, payload.clone(), "transfer".to_string(), 0, None, &keypairs),
+            signed_permit(&chain, contract_address, payload.clone(), "transfer".to_string(), 0, None, &keypairs),
+            signed_permit(&chain, contract_address, payload, "transfer".to_string(), 1, None, &keypairs),
+        ],
+    };
+    let update = permit_batch(&mut chain, &params).expect("Best-effort batch");
+    let rv: Vec<PermitResult> = update.parse_return_value().expect("PermitBatch return value");
+    assert_eq!(rv, [
+        PermitResult::Executed,
+        PermitResult::Failed(Reject::from(CustomContractError::NonceMismatch).error_code.get()),
+        PermitResult::Executed,
+    ]);
+    assert_eq!(get_balances(&chain, contract_address).0, [TokenAmountU64(98), TokenAmountU64(2)]);
+}
+
+/// Test that a permit pays its fee to the account sending the transaction.
+#[test]
+fn test_permit_fee() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let transfer = concordium_cis2::Transfer {
+        from:     ALICE_ADDR,
+        to:       Receiver::from_account(BOB),
+        token_id: TOKEN_1,
+        amount:   ContractTokenAmount::from(1),
+        data:     AdditionalData::empty(),
+    };
+    let payload = TransferParams::from(vec![transfer]);
+    let fee = PermitFee {
+        token_id: TOKEN_1,
+        amount:   TokenAmountU64(2),
+    };
+    let param = signed_permit(
+        &chain,
+        contract_address,
+        to_bytes(&payload),
+        "transfer".to_string(),
+        0,
+        Some(fee),
+        &keypairs,
+    );
+
+    // The sponsor can not take a higher fee than signed.
+    let mut tampered = signed_permit(
+        &chain,
+        contract_address,
+        to_bytes(&payload),
+        "transfer".to_string(),
+        0,
+        Some(fee),
+        &keypairs,
+    );
+    tampered.message.fee = Some(PermitFee {
+        token_id: TOKEN_1,
+        amount:   TokenAmountU64(50),
+    });
+    let update = chain
+        .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.permit".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&tampered).expect("Permit params"),
+        })
+        .expect_err("Permit with tampered fee");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::WrongSignature));
+
+    let update = chain
+        .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.permit".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&param).expect("Permit params"),
+        })
+        .expect("Permit with fee");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [
+        Event::Cis2Event(Cis2Event::Transfer(TransferEvent {
+            token_id: TOKEN_1,
+            amount:   ContractTokenAmount::from(1),
+            from:     ALICE_ADDR,
+            to:       BOB_ADDR,
+        })),
+        Event::Cis2Event(Cis2Event::Transfer(TransferEvent {
+            token_id: TOKEN_1,
+            amount:   ContractTokenAmount::from(2),
+            from:     ALICE_ADDR,
+            to:       BOB_ADDR,
+        })),
+        Event::Nonce(NonceEvent {
+            account: ALICE,
+            nonce:   0,
+        })
+    ]);
+    assert_eq!(get_balances(&chain, contract_address).0, [TokenAmountU64(97), TokenAmountU64(3)]);
+}
+
+/// Test permits signed with a public key registered to Alice.
+#[test]
+fn test_permit_public_key() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let public_key = public_key_of(&keypairs);
+    let transfer = concordium_cis2::Transfer {
+        from:     ALICE_ADDR,
+        to:       Receiver::from_account(BOB),
+        token_id: TOKEN_1,
+        amount:   ContractTokenAmount::from(1),
+        data:     AdditionalData::empty(),
+    };
+    let param = signed_public_key_permit(
+        &chain,
+        contract_address,
+        to_bytes(&TransferParams::from(vec![transfer])),
+        "transfer".to_string(),
+        &keypairs,
+    );
+    let permit_public_key = |chain: &mut Chain| {
+        chain.contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.permitPublicKey".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&param).expect("PermitPublicKey params"),
+        })
+    };
+
+    let update = permit_public_key(&mut chain).expect_err("Permit of unregistered key");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::PublicKeyNotRegistered));
+
+    // The key proves that it may be registered to Alice, but not to Bob.
+    let params = RegisterPublicKeyParams {
+        public_key,
+        signature: sign_ed25519(&keypairs, &to_bytes(&(contract_address, ALICE_ADDR))),
+    };
+    let update = chain
+        .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.registerPublicKey".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("RegisterPublicKey params"),
+        })
+        .expect_err("Register key to Bob");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::WrongSignature));
+
+    alice_update(
+        &mut chain,
+        contract_address,
+        "registerPublicKey",
+        OwnedParameter::from_serial(&params).expect("RegisterPublicKey params"),
+    )
+    .expect("Register key to Alice");
+
+    let update = permit_public_key(&mut chain).expect("Permit of registered key");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [
+        Event::Cis2Event(Cis2Event::Transfer(TransferEvent {
+            token_id: TOKEN_1,
+            amount:   ContractTokenAmount::from(1),
+            from:     ALICE_ADDR,
+            to:       BOB_ADDR,
+        })),
+        Event::PublicKeyNonce(PublicKeyNonceEvent {
+            public_key,
+            nonce: 0,
+        })
+    ]);
+    assert_eq!(get_balances(&chain, contract_address).0, [TokenAmountU64(99), TokenAmountU64(1)]);
+
+    // The permit can not be replayed.
+    let update = permit_public_key(&mut chain).expect_err("Replayed permit");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::NonceMismatch));
+
+    let nonces: NonceOfQueryResponse = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.nonceOfPublicKey".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&vec![public_key]).expect("NonceOfPublicKey params"),
+        })
+        .expect("Invoke nonceOfPublicKey")
+        .parse_return_value()
+        .expect("NonceOfPublicKey return value");
+    assert_eq!(nonces.0, [1]);
+}
+
+/// Test that permit messages signed for another domain are rejected, like
+/// messages signed for a variant before an upgrade.
+#[test]
+fn test_permit_wrong_domain() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let transfer = concordium_cis2::Transfer {
+        from:     ALICE_ADDR,
+        to:       Receiver::from_account(BOB),
+        token_id: TOKEN_1,
+        amount:   ContractTokenAmount::from(1),
+        data:     AdditionalData::empty(),
+    };
+    let payload = to_bytes(&TransferParams::from(vec![transfer]));
+    let mut param =
+        signed_permit(&chain, contract_address, payload, "transfer".to_string(), 0, None, &keypairs);
+    param.message.domain = "mint_wizard_000001_V2".to_string();
+    sign_permit(&chain, contract_address, &mut param, &keypairs);
+
+    let update = chain
+        .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.permit".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&param).expect("Permit params"),
+        })
+        .expect_err("Permit for another domain");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::WrongDomain));
+}
+
+/// Test querying nonces and cancelling signed messages by bumping the nonce.
+#[test]
+fn test_bump_nonce() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    assert_eq!(nonce_of(&chain, contract_address, vec![ALICE, BOB]).0, [0, 0]);
+
+    let update = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.bumpNonce".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&5u64).expect("BumpNonce params"),
+        })
+        .expect("Bump nonce");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::Nonce(NonceEvent {
+        account: ALICE,
+        nonce:   4,
+    })]);
+    assert_eq!(nonce_of(&chain, contract_address, vec![ALICE, BOB]).0, [5, 0]);
+
+    // The nonce can not be lowered.
+    let update = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.bumpNonce".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&3u64).expect("BumpNonce params"),
+        })
+        .expect_err("Lower nonce");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::NonceMismatch));
+}
+
+/// Test bumping the nonce with a permit.
+#[test]
+fn test_permit_bump_nonce() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let update =
+        permit(&mut chain, contract_address, to_bytes(&5u64), "bumpNonce".to_string(), keypairs);
+
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [
+        Event::Nonce(NonceEvent {
+            account: ALICE,
+            nonce:   4,
+        }),
+        Event::Nonce(NonceEvent {
+            account: ALICE,
+            nonce:   0,
+        })
+    ]);
+    assert_eq!(nonce_of(&chain, contract_address, vec![ALICE]).0, [5]);
+}
+
+{% if roles %}
+/// Test granting a role with a permit signed by the admin.
+#[test]
+fn test_permit_grant_role() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let payload = GrantRoleParams {
+        address: BOB_ADDR,
+        role:    Roles::ADMIN,
+        expiry:  None,
+    };
+    let update =
+        permit(&mut chain, contract_address, to_bytes(&payload), "grantRole".to_string(), keypairs);
+
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [
+        Event::GrantRole(GrantRoleEvent {
+            address: BOB_ADDR,
+            role:    Roles::ADMIN,
+            expiry:  None,
+        }),
+        Event::Nonce(NonceEvent {
+            account: ALICE,
+            nonce:   0,
+        })
+    ]);
+    assert!(has_role(&chain, contract_address, BOB_ADDR, Roles::ADMIN));
+}
+
+{% if updates %}
+/// Test revoking a role with a permit signed by the admin.
+#[test]
+fn test_permit_revoke_role() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let payload = RevokeRoleParams {
+        address: UPGRADER_ADDR,
+        role:    Roles::UPGRADER,
+    };
+    let update =
+        permit(&mut chain, contract_address, to_bytes(&payload), "revokeRole".to_string(), keypairs);
+
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [
+        Event::RevokeRole(RevokeRoleEvent {
+            address: UPGRADER_ADDR,
+            role:    Roles::UPGRADER,
+        }),
+        Event::Nonce(NonceEvent {
+            account: ALICE,
+            nonce:   0,
+        })
+    ]);
+    assert!(!has_role(&chain, contract_address, UPGRADER_ADDR, Roles::UPGRADER));
+}
+{% endif %}
+{% endif %}
+
+{% if pausable %}
+/// Test pausing the contract with a permit.
+#[test]
+fn test_permit_set_paused() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    {% if roles %}
+    // Permits are signed by Alice, who needs the PAUSER role.
+    let grant_role_params = GrantRoleParams {
+        address: ALICE_ADDR,
+        role:    Roles::PAUSER,
+        expiry:  None,
+    };
+    role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "grantRole",
+        OwnedParameter::from_serial(&grant_role_params).expect("GrantRole params"),
+    )
+    .expect("Grant PAUSER to Alice");
+    {% endif %}
+
+    let payload = PauseParams {
+        operations: PausedOperations::uniform(true),
+        tokens:     vec![(TOKEN_1, true)],
+    };
+    permit(&mut chain, contract_address, to_bytes(&payload), "setPaused".to_string(), keypairs);
+
+    let view = invoke_view(&mut chain, contract_address);
+    assert_eq!(view.paused, PausedOperations::uniform(true));
+    assert_eq!(view.paused_tokens, [TOKEN_1]);
+}
+{% endif %}
+
+{% if updates %}
+/// Test upgrading the contract with a permit.
+#[test]
+fn test_permit_upgrade() {
+    let (mut chain, keypairs, contract_address, module_reference) =
+        initialize_chain_and_contract();
+
+    allow_upgrade(&mut chain, contract_address, module_reference);
+
+    {% if roles %}
+    // Permits are signed by Alice, who needs the UPGRADER role.
+    let grant_role_params = GrantRoleParams {
+        address: ALICE_ADDR,
+        role:    Roles::UPGRADER,
+        expiry:  None,
+    };
+    role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "grantRole",
+        OwnedParameter::from_serial(&grant_role_params).expect("GrantRole params"),
+    )
+    .expect("Grant UPGRADER to Alice");
+    {% endif %}
+
+    let payload = UpgradeParams {
+        module:  module_reference,
+        migrate: None,
+    };
+    let update =
+        permit(&mut chain, contract_address, to_bytes(&payload), "upgrade".to_string(), keypairs);
+
+    assert!(update.effective_trace_elements().any(|e| matches!(e, ContractTraceElement::Upgraded {
+        ..
+    })));
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::Nonce(NonceEvent {
+        account: ALICE,
+        nonce:   0,
+    })]);
+}
+{% endif %}
+{% endif %}
+
+{% if burnable %}
+/// Test burning tokens.
+#[test]
+fn test_burning_tokens() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    // Create input parameters to burn one of Alice's tokens.
+    let burn_params = BurnParams {
+        owner:    ALICE_ADDR,
+        amount:   TokenAmountU64(1),
+        token_id: TOKEN_1,
+    };
+
+    // Burn one of Alice's tokens.
+    let update = chain
+        .contract_update(
+            Signer::with_one_key(),
+            ALICE,
+            ALICE_ADDR,
+            Energy::from(10000),
+            UpdateContractPayload {
+                amount:       Amount::zero(),
+                address:      contract_address,
+                receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.burn".to_string()),
+                message:      OwnedParameter::from_serial(&burn_params)
+                    .expect("Should be a valid inut parameter"),
+            },
+        )
+        .expect("Should be able to burn tokens");
+
+    // Check that the event is logged.
+    let events = update.events().flat_map(|(_addr, events)| events);
+
+    let events: Vec<Cis2Event<ContractTokenId, ContractTokenAmount>> =
+        events.map(|e| e.parse().expect("Deserialize event")).collect();
+
+    assert_eq!(events, [Cis2Event::Burn(BurnEvent {
+        owner:    ALICE_ADDR,
+        amount:   TokenAmountU64(1),
+        token_id: TOKEN_1,
+    })]);
+
+    // Check balances in state.
+    let balance_of_alice_and_bob = get_balances(&chain, contract_address);
+
+    assert_eq!(balance_of_alice_and_bob.0, [TokenAmountU64(99), TokenAmountU64(0)]);
+}
+{% endif %}
+
+{% if updates %}
+/// Upgrade the contract to itself without invoking a migration function.
+#[test]
+fn test_upgrade_without_migration_function() {
+    let (mut chain, _keypairs, contract_address, module_reference) =
+        initialize_chain_and_contract();
+
+    allow_upgrade(&mut chain, contract_address, module_reference);
+
+    let input_parameter = UpgradeParams {
+        module:  module_reference,
+        migrate: None,
+    };
+
+    // Upgrade `contract_version1` to `contract_version2`.
+    let update = chain.contract_update(
+        Signer::with_one_key(),
+        {% if roles %}
+        UPGRADER,
+        UPGRADER_ADDR,
+        {% else %}
+        ALICE,
+        ALICE_ADDR,
+        {% endif %}
+        Energy::from(100000),
+        UpdateContractPayload {
+            address:      contract_address,
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.upgrade".into()),
+            message:      OwnedParameter::from_serial(&input_parameter)
+                .expect("`UpgradeParams` should be a valid inut parameter"),
+            amount:       Amount::from_ccd(0),
+        },
+    );
+
+    assert!(
+        !update.expect("Upgrade should succeed").state_changed,
+        "State should not be changed because no `migration` function was called"
+    );
+
+    // Invoke the view entrypoint and check that the state of the contract can be
+    // read.
+    let invoke = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke view");
+
+    // Check that the tokens (as set up in the
+    // `initialize_contract_with_alice_tokens` function) are owned by Alice.
+    let rv: ViewState = invoke.parse_return_value().expect("ViewState return value");
+    assert_eq!(rv.tokens[..], [{% if wrapped_ccd %}WRAPPED_TOKEN_ID, {% endif %}TOKEN_0, TOKEN_1]);
+    assert_eq!(rv.state, vec![(ALICE_ADDR, ViewAddressState {
+        balances:  vec![(TOKEN_0, 100.into()), (TOKEN_1, 100.into())],
+        operators: Vec::new(),
+    })]);
+}
+
+/// Test that with an upgrade delay the upgrade has to be proposed and can only
+/// be executed after the delay has passed.
+#[test]
+fn test_timelocked_upgrade() {
+    let (mut chain, _keypairs, contract_address, module_reference) =
+        initialize_chain_and_contract();
+
+    allow_upgrade(&mut chain, contract_address, module_reference);
+
+    let delay = Duration::from_days(2);
+    let update = upgrader_update(
+        &mut chain,
+        contract_address,
+        "setUpgradeDelay",
+        OwnedParameter::from_serial(&delay).expect("Delay params"),
+    )
+    .expect("Set upgrade delay");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::UpgradeDelayUpdated(UpgradeDelayUpdatedEvent {
+        delay,
+    })]);
+
+    // The delay can not be decreased again.
+    let update = upgrader_update(
+        &mut chain,
+        contract_address,
+        "setUpgradeDelay",
+        OwnedParameter::from_serial(&Duration::from_millis(0)).expect("Delay params"),
+    )
+    .expect_err("Decrease upgrade delay");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::InvalidUpgradeDelay));
+
+    let input_parameter = UpgradeParams {
+        module:  module_reference,
+        migrate: None,
+    };
+
+    // A direct upgrade is rejected.
+    let update = upgrader_update(
+        &mut chain,
+        contract_address,
+        "upgrade",
+        OwnedParameter::from_serial(&input_parameter).expect("Upgrade params"),
+    )
+    .expect_err("Direct upgrade");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::UpgradeTimelocked));
+
+    // Propose the upgrade.
+    let update = upgrader_update(
+        &mut chain,
+        contract_address,
+        "proposeUpgrade",
+        OwnedParameter::from_serial(&input_parameter).expect("Upgrade params"),
+    )
+    .expect("Propose upgrade");
+
+    let executable_after = Timestamp::from_timestamp_millis(0)
+        .checked_add(delay)
+        .expect("Executable after");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::UpgradeProposed(UpgradeProposedEvent {
+        module: module_reference,
+        executable_after,
+    })]);
+
+    // The proposal is visible.
+    let invoke = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.viewUpgradeProposals".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke viewUpgradeProposals");
+    let rv: ViewUpgradeProposals = invoke.parse_return_value().expect("ViewUpgradeProposals return value");
+    assert_eq!(rv, ViewUpgradeProposals {
+        upgrade_delay: delay,
+        proposals:     vec![(module_reference, UpgradeProposal {
+            migrate: None,
+            executable_after,
+        })],
+    });
+
+    // Executing before the delay has passed fails.
+    let update = upgrader_update(
+        &mut chain,
+        contract_address,
+        "executeUpgrade",
+        OwnedParameter::from_serial(&module_reference).expect("Module reference"),
+    )
+    .expect_err("Execute upgrade too early");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::UpgradeTimelocked));
+
+    // Executing after the delay succeeds and consumes the proposal.
+    chain.tick_block_time(delay).expect("Tick block time");
+    upgrader_update(
+        &mut chain,
+        contract_address,
+        "executeUpgrade",
+        OwnedParameter::from_serial(&module_reference).expect("Module reference"),
+    )
+    .expect("Execute upgrade");
+
+    let update = upgrader_update(
+        &mut chain,
+        contract_address,
+        "executeUpgrade",
+        OwnedParameter::from_serial(&module_reference).expect("Module reference"),
+    )
+    .expect_err("Execute upgrade twice");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::UpgradeNotProposed));
+}
+
+/// Test that a proposed upgrade can be cancelled.
+#[test]
+fn test_cancel_upgrade() {
+    let (mut chain, _keypairs, contract_address, module_reference) =
+        initialize_chain_and_contract();
+
+    allow_upgrade(&mut chain, contract_address, module_reference);
+
+    let input_parameter = UpgradeParams {
+        module:  module_reference,
+        migrate: None,
+    };
+    upgrader_update(
+        &mut chain,
+        contract_address,
+        "proposeUpgrade",
+        OwnedParameter::from_serial(&input_parameter).expect("Upgrade params"),
+    )
+    .expect("Propose upgrade");
+
+    let update = upgrader_update(
+        &mut chain,
+        contract_address,
+        "cancelUpgrade",
+        OwnedParameter::from_serial(&module_reference).expect("Module reference"),
+    )
+    .expect("Cancel upgrade");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::UpgradeCancelled(UpgradeCancelledEvent {
+        module: module_reference,
+    })]);
+
+    let update = upgrader_update(
+        &mut chain,
+        contract_address,
+        "executeUpgrade",
+        OwnedParameter::from_serial(&module_reference).expect("Module reference"),
+    )
+    .expect_err("Execute cancelled upgrade");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::UpgradeNotProposed));
+}
+
+/// Test that the migration can only be invoked by the contract itself.
+#[test]
+fn test_migrate_unauthorized() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let update = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.migrate".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect_err("Migrate");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Unauthorized);
+}
+
+{% if upgradable_from %}
+/// The upgradeable variants with fewer features than this one.
+const LOWER_VARIANTS: [&str; {{ upgradable_from|length }}] = [
+    {% for variant in upgradable_from %}
+    "{{ variant }}",
+    {% endfor %}
+];
+
+/// Deploy every upgradeable variant with fewer features, upgrade it to this
+/// variant and check that the balances survive the migration.
+#[test]
+fn test_upgrade_from_lower_variants() {
+    for variant in LOWER_VARIANTS {
+        let (mut chain, _keypairs, _contract_address, module_reference) =
+            initialize_chain_and_contract();
+
+        // Deploy and initialize the lower variant.
+        let module = module_load_v1(format!("../{}/dist/module.wasm.v1", variant))
+            .expect("Module exists");
+        let deployment = chain.module_deploy_v1(SIGNER, ALICE, module).expect("Deploy valid module");
+
+        let mut premint_tokens = BTreeMap::new();
+        premint_tokens.insert(TOKEN_0, (
+            MetadataUrl {
+                url:  TOKEN_0_METADATA.to_string(),
+                hash: None,
+            }, TokenParams {
+                amount: TokenAmountU64(100),
+                max_supply: TokenAmountU64(1000),
+            }
+        ));
+        let init_params = InitParams {
+            premint_tokens,
+            premint_recipients: BTreeMap::new(),
+            owner: None,
+            metadata_base: None,
+            {% if roles %}
+            roles: Vec::new(),
+            {% endif %}
+            {% if wrapped_ccd %}
+            wrapped_metadata: wrapped_metadata(),
+            {% endif %}
+        };
+
+        let init = chain
+            .contract_init(SIGNER, ALICE, Energy::from(10000), InitContractPayload {
+                amount:    Amount::zero(),
+                mod_ref:   deployment.module_reference,
+                init_name: OwnedContractName::new_unchecked("init_{{ contract_name }}".to_string()),
+                param:     OwnedParameter::from_serial(&init_params).expect("Init params"),
+            })
+            .expect("Initialize lower variant");
+        let contract_address = init.contract_address;
+
+        // Transfer some tokens to Bob before upgrading.
+        let transfer_params = TransferParams::from(vec![concordium_cis2::Transfer {
+            from:     ALICE_ADDR,
+            to:       Receiver::Account(BOB),
+            token_id: TOKEN_0,
+            amount:   TokenAmountU64(10),
+            data:     AdditionalData::empty(),
+        }]);
+        chain
+            .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+                amount:       Amount::zero(),
+                receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.transfer".to_string()),
+                address:      contract_address,
+                message:      OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
+            })
+            .expect("Transfer tokens");
+
+        {% if roles %}
+        // With roles, only an UPGRADER can upgrade the lower variant.
+        if variant.as_bytes()[3] == b'1' {
+            let grant_role_params = GrantRoleParams {
+                address: ALICE_ADDR,
+                role:    Roles::UPGRADER,
+                expiry:  None,
+            };
+            chain
+                .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+                    amount:       Amount::zero(),
+                    receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.grantRole".to_string()),
+                    address:      contract_address,
+                    message:      OwnedParameter::from_serial(&grant_role_params)
+                        .expect("GrantRole params"),
+                })
+                .expect("UPGRADER should be granted role");
+        }
+        {% endif %}
+
+        allow_upgrade(&mut chain, contract_address, module_reference);
+
+        // Upgrade to this variant and migrate the state.
+        let input_parameter = UpgradeParams {
+            module:  module_reference,
+            migrate: Some((
+                OwnedEntrypointName::new_unchecked("migrate".to_string()),
+                OwnedParameter::empty(),
+            )),
+        };
+        chain
+            .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(100000), UpdateContractPayload {
+                amount:       Amount::zero(),
+                receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.upgrade".to_string()),
+                address:      contract_address,
+                message:      OwnedParameter::from_serial(&input_parameter).expect("Upgrade params"),
+            })
+            .unwrap_or_else(|_| panic!("Upgrade from {} should succeed", variant));
+
+        // The balances survived the migration.
+        let invoke = chain
+            .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+                amount:       Amount::zero(),
+                receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
+                address:      contract_address,
+                message:      OwnedParameter::empty(),
+            })
+            .expect("Invoke view");
+        let rv: ViewState = invoke.parse_return_value().expect("ViewState return value");
+        assert_eq!(rv.tokens[..], [{% if wrapped_ccd %}WRAPPED_TOKEN_ID, {% endif %}TOKEN_0]);
+        assert_eq!(rv.state, vec![
+            (ALICE_ADDR, ViewAddressState {
+                balances:  vec![(TOKEN_0, 90.into())],
+                operators: Vec::new(),
+            }),
+            (BOB_ADDR, ViewAddressState {
+                balances:  vec![(TOKEN_0, 10.into())],
+                operators: Vec::new(),
+            }),
+        ], "Balances after upgrading from {}", variant);
+        {% if roles %}
+        assert!(
+            rv.roles
+                .iter()
+                .any(|(address, roles)| *address == ALICE_ADDR && roles.contains(&(Roles::ADMIN, None))),
+            "Alice should be ADMIN after upgrading from {}",
+            variant
+        );
+        {% endif %}
+
+        // The migrated state can be updated.
+        chain
+            .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+                amount:       Amount::zero(),
+                receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.transfer".to_string()),
+                address:      contract_address,
+                message:      OwnedParameter::from_serial(&TransferParams::from(vec![
+                    concordium_cis2::Transfer {
+                        from:     BOB_ADDR,
+                        to:       Receiver::Account(ALICE),
+                        token_id: TOKEN_0,
+                        amount:   TokenAmountU64(10),
+                        data:     AdditionalData::empty(),
+                    },
+                ]))
+                .expect("Transfer params"),
+            })
+            .expect("Transfer tokens after upgrade");
+    }
+}
+{% endif %}
+
+{% if migrate_v3 %}
+/// Deploy the V3 variant with the same binary code, whose state is not
+/// versioned, upgrade it with `migrateV3` and check that the balances, the
+/// circulating supply and the roles survive the migration.
+#[test]
+fn test_migrate_v3() {
+    let mut chain = Chain::new();
+    chain.create_account(Account::new(ALICE, ACC_INITIAL_BALANCE));
+    chain.create_account(Account::new(BOB, ACC_INITIAL_BALANCE));
+
+    let module = module_load_v1("../v3_{{ code }}/dist/module.wasm.v1").expect("V3 module exists");
+    let deployment = chain.module_deploy_v1(SIGNER, ALICE, module).expect("Deploy V3 module");
+    let mut premint_tokens = BTreeMap::new();
+    premint_tokens.insert(TOKEN_0, (
+        MetadataUrl {
+            url:  TOKEN_0_METADATA.to_string(),
+            hash: None,
+        },
+        TokenParams {
+            amount:     TokenAmountU64(100),
+            max_supply: TokenAmountU64(1000),
+        },
+    ));
+    let init = chain
+        .contract_init(SIGNER, ALICE, Energy::from(10000), InitContractPayload {
+            amount:    Amount::zero(),
+            mod_ref:   deployment.module_reference,
+            init_name: OwnedContractName::new_unchecked("init_{{ contract_name }}".to_string()),
+            param:     OwnedParameter::from_serial(&premint_tokens).expect("Init params"),
+        })
+        .expect("Initialize V3 contract");
+    let contract_address = init.contract_address;
+
+    let transfer_params = TransferParams::from(vec![concordium_cis2::Transfer {
+        from:     ALICE_ADDR,
+        to:       Receiver::Account(BOB),
+        token_id: TOKEN_0,
+        amount:   TokenAmountU64(30),
+        data:     AdditionalData::empty(),
+    }]);
+    alice_update(
+        &mut chain,
+        contract_address,
+        "transfer",
+        OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
+    )
+    .expect("Transfer tokens");
+    {% if burnable %}
+    // V3 did not lower the circulating supply on burns.
+    alice_update(
+        &mut chain,
+        contract_address,
+        "burn",
+        OwnedParameter::from_serial(&(ALICE_ADDR, TOKEN_0, TokenAmountU64(20))).expect("Burn params"),
+    )
+    .expect("Burn tokens");
+    {% endif %}
+    {% if roles %}
+    // Roles of V3 are serialized as their index, without an expiry.
+    alice_update(
+        &mut chain,
+        contract_address,
+        "grantRole",
+        OwnedParameter::from_serial(&(ALICE_ADDR, 1u8)).expect("GrantRole params"),
+    )
+    .expect("Grant upgrader role");
+    {% if mintable %}
+    alice_update(
+        &mut chain,
+        contract_address,
+        "grantRole",
+        OwnedParameter::from_serial(&(ALICE_ADDR, {% if pausable %}3u8{% else %}2u8{% endif %}))
+            .expect("GrantRole params"),
+    )
+    .expect("Grant minter role");
+    {% endif %}
+    {% endif %}
+
+    let module = module_load_v1("dist/module.wasm.v1").expect("Module exists");
+    let deployment = chain.module_deploy_v1(SIGNER, ALICE, module).expect("Deploy valid module");
+    let params = UpgradeParams {
+        module:  deployment.module_reference,
+        migrate: Some((OwnedEntrypointName::new_unchecked("migrateV3".to_string()), OwnedParameter::empty())),
+    };
+    chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(100000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.upgrade".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("Upgrade params"),
+        })
+        .expect("Upgrade V3 contract");
+
+    let rv: ViewState = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke view")
+        .parse_return_value()
+        .expect("ViewState return value");
+    assert_eq!(rv.owner, ALICE_ADDR);
+    assert_eq!(rv.state, vec![
+        (ALICE_ADDR, ViewAddressState {
+            balances:  vec![(TOKEN_0, {% if burnable %}50{% else %}70{% endif %}.into())],
+            operators: Vec::new(),
+        }),
+        (BOB_ADDR, ViewAddressState {
+            balances:  vec![(TOKEN_0, 30.into())],
+            operators: Vec::new(),
+        }),
+    ]);
+    {% if roles %}
+    assert!(has_role(&chain, contract_address, ALICE_ADDR, Roles::ADMIN));
+    assert!(has_role(&chain, contract_address, ALICE_ADDR, Roles::UPGRADER));
+    {% if mintable %}
+    assert!(has_role(&chain, contract_address, ALICE_ADDR, Roles::MINTER));
+    {% endif %}
+    {% endif %}
+    {% if mintable %}
+
+    // The supply is recounted, so only the tokens held count towards the max
+    // supply.
+    mint_as(&mut chain, contract_address, ALICE, TOKEN_0, {% if burnable %}920{% else %}900{% endif %})
+        .expect("Mint up to the max supply");
+    mint_as(&mut chain, contract_address, ALICE, TOKEN_0, 1).expect_err("Mint above the max supply");
+    {% endif %}
+}
+{% endif %}
+
+/// Test that upgrades to modules outside the allowlist are rejected and that
+/// the allowlist can be queried.
+#[test]
+fn test_upgrade_allowlist() {
+    let (mut chain, _keypairs, contract_address, module_reference) =
+        initialize_chain_and_contract();
+
+    let input_parameter = UpgradeParams {
+        module:  module_reference,
+        migrate: None,
+    };
+
+    // The module is not on the allowlist yet.
+    let update = upgrader_update(
+        &mut chain,
+        contract_address,
+        "upgrade",
+        OwnedParameter::from_serial(&input_parameter).expect("Upgrade params"),
+    )
+    .expect_err("Upgrade to a module not on the allowlist");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::UpgradeModuleNotAllowed));
+
+    let update = upgrader_update(
+        &mut chain,
+        contract_address,
+        "proposeUpgrade",
+        OwnedParameter::from_serial(&input_parameter).expect("Upgrade params"),
+    )
+    .expect_err("Propose a module not on the allowlist");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::UpgradeModuleNotAllowed));
+
+    // Only the admin can update the allowlist.
+    let params = UpdateUpgradeAllowlistParams {
+        updates: vec![(module_reference, true)],
+    };
+    let update = chain
+        .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.updateUpgradeAllowlist".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("Allowlist params"),
+        })
+        .expect_err("Update allowlist");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Unauthorized);
+
+    let update = allow_upgrade(&mut chain, contract_address, module_reference);
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::UpgradeAllowlistUpdated(UpgradeAllowlistUpdatedEvent {
+        module:  module_reference,
+        allowed: true,
+    })]);
+
+    let invoke = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.upgradeAllowlist".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke upgradeAllowlist");
+    let rv: Vec<ModuleReference> = invoke.parse_return_value().expect("Allowlist return value");
+    assert_eq!(rv, vec![module_reference]);
+
+    upgrader_update(
+        &mut chain,
+        contract_address,
+        "upgrade",
+        OwnedParameter::from_serial(&input_parameter).expect("Upgrade params"),
+    )
+    .expect("Upgrade to a module on the allowlist");
+}
+
+/// Add a module to the upgrade allowlist as Alice, who is the owner and admin.
+fn allow_upgrade(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    module: ModuleReference,
+) -> ContractInvokeSuccess {
+    let params = UpdateUpgradeAllowlistParams {
+        updates: vec![(module, true)],
+    };
+    chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.updateUpgradeAllowlist".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("Allowlist params"),
+        })
+        .expect("Update allowlist")
+}
+
+/// Call an entrypoint as the account allowed to upgrade the contract.
+fn upgrader_update(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    entrypoint: &str,
+    message: OwnedParameter,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(
+        SIGNER,
+        {% if roles %}
+        UPGRADER,
+        UPGRADER_ADDR,
+        {% else %}
+        ALICE,
+        ALICE_ADDR,
+        {% endif %}
+        Energy::from(100000),
+        UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked(format!("{{ contract_name }}.{}", entrypoint)),
+            address:      contract_address,
+            message,
+        },
+    )
+}
+{% endif %}
+
+{% if pausable %}
+/// Test that the pause/unpause entrypoints correctly sets the pause value in
+/// the state.
+#[test]
+fn test_pause_functionality() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    // Pause the contract.
+    chain
+        .contract_update(
+            SIGNER,
+            {% if roles %}
+            PAUSER,
+            PAUSER_ADDR,
+            {% else %}
+            ALICE,
+            ALICE_ADDR,
+            {% endif %}
+            Energy::from(10000),
+            UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.setPaused".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&true).expect("Pause params"),
+        })
+        .expect("Pause");
+
+    // Check that the contract is now paused.
+    assert_eq!(invoke_view(&mut chain, contract_address).paused, PausedOperations::uniform(true));
+
+    // Unpause the contract.
+    chain
+        .contract_update(
+            SIGNER,
+            {% if roles %}
+            PAUSER,
+            PAUSER_ADDR,
+            {% else %}
+            ALICE,
+            ALICE_ADDR,
+            {% endif %}
+            Energy::from(10000),
+            UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.setPaused".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&false).expect("Unpause params"),
+        })
+        .expect("Unpause");
+    // Check that the contract is now unpaused.
+    assert_eq!(invoke_view(&mut chain, contract_address).paused, PausedOperations::uniform(false));
+}
+
+/// Test that pausing a single operation or a single token leaves the rest of
+/// the contract usable.
+#[test]
+fn test_granular_pause() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    // Pause transfers of `TOKEN_0` and all operator updates.
+    let params = PauseParams {
+        operations: PausedOperations {
+            update_operator: true,
+            ..PausedOperations::default()
+        },
+        tokens:     vec![(TOKEN_0, true)],
+    };
+    chain
+        .contract_update(
+            SIGNER,
+            {% if roles %}
+            PAUSER,
+            PAUSER_ADDR,
+            {% else %}
+            ALICE,
+            ALICE_ADDR,
+            {% endif %}
+            Energy::from(10000),
+            UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.setPaused".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("Pause params"),
+        })
+        .expect("Pause");
+
+    let view = invoke_view(&mut chain, contract_address);
+    assert_eq!(view.paused, params.operations);
+    assert_eq!(view.paused_tokens, vec![TOKEN_0]);
+
+    // Transferring the paused token fails.
+    let transfer_params = TransferParams::from(vec![concordium_cis2::Transfer {
+        from:     ALICE_ADDR,
+        to:       Receiver::Account(BOB),
+        token_id: TOKEN_0,
+        amount:   TokenAmountU64(1),
+        data:     AdditionalData::empty(),
+    }]);
+    let update = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.transfer".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
+        })
+        .expect_err("Transfer paused token");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::TokenPaused));
+
+    // Transferring another token still succeeds.
+    let transfer_params = TransferParams::from(vec![concordium_cis2::Transfer {
+        from:     ALICE_ADDR,
+        to:       Receiver::Account(BOB),
+        token_id: TOKEN_1,
+        amount:   TokenAmountU64(1),
+        data:     AdditionalData::empty(),
+    }]);
+    chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.transfer".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
+        })
+        .expect("Transfer tokens");
+
+    // Updating operators is paused.
+    let params = UpdateOperatorParams(vec![UpdateOperator {
+        update:   OperatorUpdate::Add,
+        operator: BOB_ADDR,
+    }]);
+    let update = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.updateOperator".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("UpdateOperator params"),
+        })
+        .expect_err("Update operator");
+    assert_contract_paused_error(&update);
+}
+
+/// Test that only the PAUSER can pause/unpause the contract.
+#[test]
+fn test_pause_unpause_unauthorized() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    // Pause the contract as Bob, who is not the PAUSER.
+    let update = chain
+        .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.setPaused".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&true).expect("Pause params"),
+        })
+        .expect_err("Pause");
+
+    // Check that the correct error is returned.
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Unauthorized);
+}
+
+/// Test that one can NOT call non-admin state-mutative functions (burn,
+/// mint, transfer, updateOperator) when the contract is paused.
+#[test]
+fn test_no_execution_of_state_mutative_functions_when_paused() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    // Pause the contract.
+    chain
+        .contract_update(
+            SIGNER,
+            {% if roles %}
+            PAUSER,
+            PAUSER_ADDR,
+            {% else %}
+            ALICE,
+            ALICE_ADDR,
+            {% endif %}
+            Energy::from(10000),
+            UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.setPaused".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&true).expect("Pause params"),
+        })
+        .expect("Pause");
+
+    // Try to transfer 1 token from Alice to Bob.
+    let transfer_params = TransferParams::from(vec![concordium_cis2::Transfer {
+        from:     ALICE_ADDR,
+        to:       Receiver::Account(BOB),
+        token_id: TOKEN_0,
+        amount:   TokenAmountU64(1),
+        data:     AdditionalData::empty(),
+    }]);
+    let update_transfer = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.transfer".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
+        })
+        .expect_err("Transfer tokens");
+    assert_contract_paused_error(&update_transfer);
+
+    // Try to add Bob as an operator for Alice.
+    let params = UpdateOperatorParams(vec![UpdateOperator {
+        update:   OperatorUpdate::Add,
+        operator: BOB_ADDR,
+    }]);
+    let update_operator = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.updateOperator".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("UpdateOperator params"),
+        })
+        .expect_err("Update operator");
+    assert_contract_paused_error(&update_operator);
+
+    {% if mintable %}
+    // Try to mint tokens.
+    let token_params = TokenParams {
+        amount: TokenAmountU64(10),
+        max_supply: TokenAmountU64(1000),
+    };
+
+    let mut mint_tokens = BTreeMap::new();
+    mint_tokens.insert(TOKEN_0, (
+        MetadataUrl {
+            url:  TOKEN_0_METADATA.to_string(),
+            hash: None,
+        }, token_params
+    ));
+
+    let params = MintParams {
+        owner:      ALICE_ADDR,
+        tokens:     mint_tokens,
+    };
+
+    let update_operator = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.mint".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("Mint params"),
+        })
+        .expect_err("Update operator");
+    assert_contract_paused_error(&update_operator);
+    {% endif %}
+
+    {% if burnable %}
+    // Try to burn tokens.
+    let params = BurnParams {
+        owner:    ALICE_ADDR,
+        amount:   TokenAmountU64(1),
+        token_id: TOKEN_0,
+    };
+
+    let update_operator = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.burn".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("Burn params"),
+        })
+        .expect_err("Update operator");
+    assert_contract_paused_error(&update_operator);
+    {% endif %}
+}
+
+/// Check that the returned error is `ContractPaused`.
+fn assert_contract_paused_error(update: &ContractInvokeError) {
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::Paused));
+}
+
+/// Get the result of the view entrypoint.
+fn invoke_view(chain: &mut Chain, contract_address: ContractAddress) -> ViewState {
+    let invoke = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.view".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke view");
+    invoke.parse_return_value().expect("Return value")
+}
+{% endif %}
+
+{% if roles %}
+/// Test querying role holders and renouncing roles.
+#[test]
+fn test_role_queries() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let grant_role_params = GrantRoleParams {
+        address: BOB_ADDR,
+        role:    Roles::ADMIN,
+        expiry:  None,
+    };
+    role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "grantRole",
+        OwnedParameter::from_serial(&grant_role_params).expect("GrantRole params"),
+    )
+    .expect("Grant ADMIN to Bob");
+
+    assert!(has_role(&chain, contract_address, BOB_ADDR, Roles::ADMIN));
+
+    let mut admins = role_members(&chain, contract_address, Roles::ADMIN, 0, 10);
+    admins.sort();
+    let mut expected = vec![(ALICE_ADDR, None), (BOB_ADDR, None)];
+    expected.sort();
+    assert_eq!(admins, expected);
+
+    // Pages do not overlap.
+    let first = role_members(&chain, contract_address, Roles::ADMIN, 0, 1);
+    let second = role_members(&chain, contract_address, Roles::ADMIN, 1, 1);
+    assert_eq!([first, second].concat(), role_members(&chain, contract_address, Roles::ADMIN, 0, 2));
+    assert_eq!(role_members(&chain, contract_address, Roles::ADMIN, 2, 1), []);
+
+    let update = role_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "renounceRole",
+        OwnedParameter::from_serial(&Roles::ADMIN).expect("Role"),
+    )
+    .expect("Bob renounces ADMIN");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::RevokeRole(RevokeRoleEvent {
+        address: BOB_ADDR,
+        role:    Roles::ADMIN,
+    })]);
+
+    assert!(!has_role(&chain, contract_address, BOB_ADDR, Roles::ADMIN));
+    assert_eq!(role_members(&chain, contract_address, Roles::ADMIN, 0, 10), [(ALICE_ADDR, None)]);
+
+    let update = role_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "renounceRole",
+        OwnedParameter::from_serial(&Roles::ADMIN).expect("Role"),
+    )
+    .expect_err("Renounce a role twice");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::RoleWasNotGranted));
+}
+
+/// Test that the last admin can neither be revoked nor renounce the role.
+#[test]
+fn test_last_admin() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let revoke_role_params = RevokeRoleParams {
+        address: ALICE_ADDR,
+        role:    Roles::ADMIN,
+    };
+    let update = role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "revokeRole",
+        OwnedParameter::from_serial(&revoke_role_params).expect("RevokeRole params"),
+    )
+    .expect_err("Revoke the last admin");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::LastAdmin));
+
+    let update = role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "renounceRole",
+        OwnedParameter::from_serial(&Roles::ADMIN).expect("Role"),
+    )
+    .expect_err("Renounce the last admin");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::LastAdmin));
+
+    assert!(has_role(&chain, contract_address, ALICE_ADDR, Roles::ADMIN));
+}
+
+/// Test that a role granted with an expiry is lost once the block time passes
+/// the expiry.
+#[test]
+fn test_time_bounded_role() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let expiry = chain.block_time().checked_add(Duration::from_days(1)).expect("Expiry");
+    let grant_role_params = GrantRoleParams {
+        address: BOB_ADDR,
+        role:    Roles::ADMIN,
+        expiry:  Some(expiry),
+    };
+    let update = role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "grantRole",
+        OwnedParameter::from_serial(&grant_role_params).expect("GrantRole params"),
+    )
+    .expect("Grant ADMIN to Bob");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::GrantRole(GrantRoleEvent {
+        address: BOB_ADDR,
+        role:    Roles::ADMIN,
+        expiry:  Some(expiry),
+    })]);
+
+    assert!(has_role(&chain, contract_address, BOB_ADDR, Roles::ADMIN));
+    assert!(role_members(&chain, contract_address, Roles::ADMIN, 0, 10)
+        .contains(&(BOB_ADDR, Some(expiry))));
+
+    // An admin that loses the role over time does not keep Alice from being
+    // the last admin.
+    let revoke_role_params = RevokeRoleParams {
+        address: ALICE_ADDR,
+        role:    Roles::ADMIN,
+    };
+    let update = role_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "revokeRole",
+        OwnedParameter::from_serial(&revoke_role_params).expect("RevokeRole params"),
+    )
+    .expect_err("Revoke the last permanent admin");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::LastAdmin));
+
+    chain.tick_block_time(Duration::from_days(1)).expect("Advance block time");
+
+    assert!(!has_role(&chain, contract_address, BOB_ADDR, Roles::ADMIN));
+
+    let update = role_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "grantRole",
+        OwnedParameter::from_serial(&grant_role_params).expect("GrantRole params"),
+    )
+    .expect_err("Grant a role as an expired admin");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Unauthorized);
+
+    // The expired grant can be renewed, but not with an expiry in the past.
+    let update = role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "grantRole",
+        OwnedParameter::from_serial(&grant_role_params).expect("GrantRole params"),
+    )
+    .expect_err("Grant a role with an expiry in the past");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::InvalidRoleExpiry));
+
+    let grant_role_params = GrantRoleParams {
+        address: BOB_ADDR,
+        role:    Roles::ADMIN,
+        expiry:  None,
+    };
+    role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "grantRole",
+        OwnedParameter::from_serial(&grant_role_params).expect("GrantRole params"),
+    )
+    .expect("Renew ADMIN of Bob");
+    assert!(has_role(&chain, contract_address, BOB_ADDR, Roles::ADMIN));
+}
+
+/// Test that the ATTRIBUTEUPDATER role allows setting token attributes.
+#[test]
+fn test_attribute_updater() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let params = SetAttributesParams {
+        token_id:         TOKEN_0,
+        attributes:       vec![("level".to_string(), Some("2".to_string()))],
+        refresh_metadata: false,
+    };
+    let update = role_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "setAttributes",
+        OwnedParameter::from_serial(&params).expect("SetAttributes params"),
+    )
+    .expect_err("Set attributes without role");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Unauthorized);
+
+    // The owner needs the role as well.
+    let update = role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "setAttributes",
+        OwnedParameter::from_serial(&params).expect("SetAttributes params"),
+    )
+    .expect_err("Set attributes as owner without role");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Unauthorized);
+
+    let grant_role_params = GrantRoleParams {
+        address: BOB_ADDR,
+        role:    Roles::ATTRIBUTEUPDATER,
+        expiry:  None,
+    };
+    role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "grantRole",
+        OwnedParameter::from_serial(&grant_role_params).expect("GrantRole params"),
+    )
+    .expect("Grant ATTRIBUTEUPDATER to Bob");
+
+    role_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "setAttributes",
+        OwnedParameter::from_serial(&params).expect("SetAttributes params"),
+    )
+    .expect("Set attributes as ATTRIBUTEUPDATER");
+}
+
+{% if mintable %}
+/// Test that minters can only mint within their quota and token ID ranges,
+/// while the owner is not limited.
+#[test]
+fn test_minter_quota() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let grant_role_params = GrantRoleParams {
+        address: BOB_ADDR,
+        role:    Roles::MINTER,
+        expiry:  None,
+    };
+    role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "grantRole",
+        OwnedParameter::from_serial(&grant_role_params).expect("GrantRole params"),
+    )
+    .expect("Grant MINTER to Bob");
+
+    let quota = MinterQuotaParams {
+        total:           Some(TokenAmountU64(50)),
+        per_token:       vec![(TOKEN_1, TokenAmountU64(20))],
+        token_id_ranges: vec![(TokenIdU8(0), TokenIdU8(100))],
+    };
+    let params = SetMinterQuotaParams {
+        minter: BOB_ADDR,
+        quota:  Some(quota.clone()),
+    };
+    role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "setMinterQuota",
+        OwnedParameter::from_serial(&params).expect("SetMinterQuota params"),
+    )
+    .expect("Set quota of Bob");
+    assert_eq!(view_minter_quota(&chain, contract_address, BOB_ADDR), Some(quota));
+
+    let update = mint_as(&mut chain, contract_address, BOB, TOKEN_1, 21)
+        .expect_err("Mint above the token quota");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MinterQuotaExceeded));
+
+    mint_as(&mut chain, contract_address, BOB, TOKEN_1, 20).expect("Mint the token quota");
+
+    let update = mint_as(&mut chain, contract_address, BOB, TokenIdU8(200), 1)
+        .expect_err("Mint outside of the token ID ranges");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::TokenIdNotAllowed));
+
+    let update = mint_as(&mut chain, contract_address, BOB, TOKEN_0, 31)
+        .expect_err("Mint above the total quota");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MinterQuotaExceeded));
+
+    mint_as(&mut chain, contract_address, BOB, TOKEN_0, 30).expect("Mint the total quota");
+    assert_eq!(
+        view_minter_quota(&chain, contract_address, BOB_ADDR),
+        Some(MinterQuotaParams {
+            total:           Some(TokenAmountU64(0)),
+            per_token:       vec![(TOKEN_1, TokenAmountU64(0))],
+            token_id_ranges: vec![(TokenIdU8(0), TokenIdU8(100))],
+        })
+    );
+
+    // The owner is not limited.
+    mint_as(&mut chain, contract_address, ALICE, TokenIdU8(200), 100).expect("Mint as the owner");
+
+    let params = SetMinterQuotaParams {
+        minter: BOB_ADDR,
+        quota:  None,
+    };
+    role_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "setMinterQuota",
+        OwnedParameter::from_serial(&params).expect("SetMinterQuota params"),
+    )
+    .expect("Remove quota of Bob");
+    assert_eq!(view_minter_quota(&chain, contract_address, BOB_ADDR), None);
+    mint_as(&mut chain, contract_address, BOB, TOKEN_0, 1).expect("Mint without quota");
+}
+
+/// Get the remaining quota of a minter.
+fn view_minter_quota(
+    chain: &Chain,
+    contract_address: ContractAddress,
+    minter: Address,
+) -> Option<MinterQuotaParams> {
+    chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.viewMinterQuota".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&minter).expect("Minter"),
+        })
+        .expect("Invoke viewMinterQuota")
+        .parse_return_value()
+        .expect("ViewMinterQuota return value")
+}
+{% endif %}
+
+/// Call an entrypoint of the contract as the given account.
+fn role_update(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    sender: AccountAddress,
+    entrypoint: &str,
+    message: OwnedParameter,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(SIGNER, sender, Address::Account(sender), Energy::from(10000), UpdateContractPayload {
+        amount:       Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked(format!("{{ contract_name }}.{}", entrypoint)),
+        address:      contract_address,
+        message,
+    })
+}
+
+/// Check whether the address holds the role.
+fn has_role(chain: &Chain, contract_address: ContractAddress, address: Address, role: Roles) -> bool {
+    let params = HasRoleQueryParams {
+        address,
+        role,
+    };
+    chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.hasRole".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("HasRole params"),
+        })
+        .expect("Invoke hasRole")
+        .parse_return_value()
+        .expect("HasRole return value")
+}
+
+/// Get a page of the addresses holding the role.
+fn role_members(
+    chain: &Chain,
+    contract_address: ContractAddress,
+    role: Roles,
+    skip: u32,
+    limit: u32,
+) -> Vec<(Address, Option<Timestamp>)> {
+    let params = RoleMembersQueryParams {
+        role,
+        skip,
+        limit,
+    };
+    chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.roleMembers".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("RoleMembers params"),
+        })
+        .expect("Invoke roleMembers")
+        .parse_return_value()
+        .expect("RoleMembers return value")
+}
+{% endif %}
+
+{% if multisig %}
+/// Test that once multisig is enabled, privileged entrypoints only run as
+/// operations approved by enough signers.
+#[test]
+fn test_multisig_operation() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let params = UpdateMultisigParams {
+        signers:   vec![ALICE, BOB],
+        threshold: 2,
+    };
+    multisig_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "updateMultisig",
+        OwnedParameter::from_serial(&params).expect("Multisig params"),
+    )
+    .expect("Enable multisig");
+
+    // Alice can no longer call privileged entrypoints herself.
+    let params = UpdateMultisigParams {
+        signers:   vec![ALICE, BOB],
+        threshold: 1,
+    };
+    let update = multisig_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "updateMultisig",
+        OwnedParameter::from_serial(&params).expect("Multisig params"),
+    )
+    .expect_err("Update multisig directly");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Unauthorized);
+
+    let operation = ProposeOperationParams {
+        entrypoint: OwnedEntrypointName::new_unchecked("updateMultisig".to_string()),
+        parameter:  OwnedParameter::from_serial(&params).expect("Multisig params"),
+    };
+    let update = multisig_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "proposeOperation",
+        OwnedParameter::from_serial(&operation).expect("Operation params"),
+    )
+    .expect("Propose operation");
+    let operation_id: u64 = update.parse_return_value().expect("Operation ID");
+    assert_eq!(operation_id, 0);
+
+    let multisig = view_multisig(&chain, contract_address);
+    assert_eq!(multisig.threshold, 2);
+    assert_eq!(multisig.operations, [(0, MultisigOperation {
+        entrypoint: operation.entrypoint,
+        parameter:  operation.parameter,
+        approvals:  vec![ALICE],
+    })]);
+
+    // Bob's approval meets the threshold and executes the operation.
+    let update = multisig_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "approveOperation",
+        OwnedParameter::from_serial(&operation_id).expect("Operation ID"),
+    )
+    .expect("Approve operation");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [
+        Event::OperationApproved(OperationApprovedEvent {
+            operation_id,
+            signer: BOB,
+        }),
+        Event::OperationExecuted(OperationExecutedEvent {
+            operation_id,
+        }),
+    ]);
+
+    let multisig = view_multisig(&chain, contract_address);
+    assert_eq!(multisig.threshold, 1);
+    assert_eq!(multisig.operations, []);
+
+    let update = multisig_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "approveOperation",
+        OwnedParameter::from_serial(&operation_id).expect("Operation ID"),
+    )
+    .expect_err("Approve executed operation");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::UnknownOperation));
+}
+
+/// Test that a signature of a signer approves an operation when relayed by
+/// another account.
+#[test]
+fn test_multisig_signed_approval() {
+    let (mut chain, keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let params = UpdateMultisigParams {
+        signers:   vec![ALICE, BOB],
+        threshold: 2,
+    };
+    multisig_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "updateMultisig",
+        OwnedParameter::from_serial(&params).expect("Multisig params"),
+    )
+    .expect("Enable multisig");
+
+    let params = UpdateMultisigParams {
+        signers:   vec![BOB],
+        threshold: 1,
+    };
+    let operation = ProposeOperationParams {
+        entrypoint: OwnedEntrypointName::new_unchecked("updateMultisig".to_string()),
+        parameter:  OwnedParameter::from_serial(&params).expect("Multisig params"),
+    };
+    let update = multisig_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "proposeOperation",
+        OwnedParameter::from_serial(&operation).expect("Operation params"),
+    )
+    .expect("Propose operation");
+    let operation_id: u64 = update.parse_return_value().expect("Operation ID");
+
+    let signature_map = BTreeMap::from([(0u8, CredentialSignatures {
+        sigs: BTreeMap::from([(0u8, concordium_std::Signature::Ed25519(DUMMY_SIGNATURE))]),
+    })]);
+    let mut approval = SignedApproval {
+        signature: AccountSignatures {
+            sigs: signature_map,
+        },
+        signer:    ALICE,
+        message:   ApprovalMessage {
+            contract_address,
+            operation_id,
+            timestamp: Timestamp::from_timestamp_millis(10_000_000_000),
+        },
+    };
+
+    // Get the message hash to be signed.
+    let invoke = chain
+        .contract_invoke(BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            address:      contract_address,
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.viewApprovalMessageHash".to_string()),
+            message:      OwnedParameter::from_serial(&approval).expect("Approval params"),
+        })
+        .expect("Should be able to query viewApprovalMessageHash");
+    let message_hash: HashSha2256 =
+        from_bytes(&invoke.return_value).expect("Should return a valid result");
+    approval.signature = keypairs.sign_message(&to_bytes(&message_hash));
+
+    let params = ApproveOperationSignedParams {
+        approvals: vec![approval],
+    };
+    multisig_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "approveOperationSigned",
+        OwnedParameter::from_serial(&params).expect("Approval params"),
+    )
+    .expect("Approve operation with signature");
+
+    let multisig = view_multisig(&chain, contract_address);
+    assert_eq!(multisig.signers, [BOB]);
+    assert_eq!(multisig.threshold, 1);
+    assert_eq!(multisig.operations, []);
+}
+
+/// Test that only signers can propose operations, and only while multisig is
+/// enabled.
+#[test]
+fn test_multisig_unauthorized() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let params = UpdateMultisigParams {
+        signers:   vec![ALICE],
+        threshold: 1,
+    };
+    let operation = ProposeOperationParams {
+        entrypoint: OwnedEntrypointName::new_unchecked("updateMultisig".to_string()),
+        parameter:  OwnedParameter::from_serial(&params).expect("Multisig params"),
+    };
+    let update = multisig_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "proposeOperation",
+        OwnedParameter::from_serial(&operation).expect("Operation params"),
+    )
+    .expect_err("Propose operation without multisig");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::MultisigDisabled));
+
+    let invalid_params = UpdateMultisigParams {
+        signers:   vec![ALICE],
+        threshold: 2,
+    };
+    let update = multisig_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "updateMultisig",
+        OwnedParameter::from_serial(&invalid_params).expect("Multisig params"),
+    )
+    .expect_err("Threshold above the number of signers");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::InvalidThreshold));
+
+    multisig_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "updateMultisig",
+        OwnedParameter::from_serial(&params).expect("Multisig params"),
+    )
+    .expect("Enable multisig");
+
+    let update = multisig_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "proposeOperation",
+        OwnedParameter::from_serial(&operation).expect("Operation params"),
+    )
+    .expect_err("Propose operation as Bob");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::NotASigner));
+}
+
+/// Test that privileged entrypoints can not be called by the contract itself
+/// through a receive hook, which is how approved operations are executed.
+#[test]
+fn test_multisig_self_call() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    let params = UpdateMultisigParams {
+        signers:   vec![ALICE],
+        threshold: 1,
+    };
+    multisig_update(
+        &mut chain,
+        contract_address,
+        ALICE,
+        "updateMultisig",
+        OwnedParameter::from_serial(&params).expect("Multisig params"),
+    )
+    .expect("Enable multisig");
+
+    // Bob transfers nothing to `updateMultisig` of the contract, which would
+    // call it with the contract as the sender.
+    let transfer_params = TransferParams::from(vec![concordium_cis2::Transfer {
+        from:     BOB_ADDR,
+        to:       Receiver::from_contract(
+            contract_address,
+            OwnedEntrypointName::new_unchecked("updateMultisig".to_string()),
+        ),
+        token_id: TOKEN_1,
+        amount:   TokenAmountU64(0),
+        data:     AdditionalData::empty(),
+    }]);
+    let update = multisig_update(
+        &mut chain,
+        contract_address,
+        BOB,
+        "transfer",
+        OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
+    )
+    .expect_err("Transfer to updateMultisig of the contract");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::InvalidReceiver));
+
+    let multisig = view_multisig(&chain, contract_address);
+    assert_eq!(multisig.signers, [ALICE]);
+}
+
+/// Call an entrypoint of the contract as the given account.
+fn multisig_update(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    sender: AccountAddress,
+    entrypoint: &str,
+    message: OwnedParameter,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(SIGNER, sender, Address::Account(sender), Energy::from(100000), UpdateContractPayload {
+        amount:       Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked(format!("{{ contract_name }}.{}", entrypoint)),
+        address:      contract_address,
+        message,
+    })
+}
+
+/// Get the signers, the threshold and the pending operations.
+fn view_multisig(chain: &Chain, contract_address: ContractAddress) -> ViewMultisig {
+    chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.viewMultisig".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke viewMultisig")
+        .parse_return_value()
+        .expect("ViewMultisig return value")
+}
+{% endif %}
+
+{% if receive_tokens %}
+/// Test that tokens of another CIS-2 contract are recorded when received, and
+/// can be recovered by an admin.
+#[test]
+fn test_receive_and_recover_tokens() {
+    let (mut chain, _keypairs, contract_address, module_reference) =
+        initialize_chain_and_contract();
+    let source_address = init_source_contract(&mut chain, module_reference);
+
+    // Alice sends tokens of the source contract to the contract.
+    let transfer_params = TransferParams::from(vec![concordium_cis2::Transfer {
+        from:     ALICE_ADDR,
+        to:       Receiver::from_contract(
+            contract_address,
+            OwnedEntrypointName::new_unchecked("onReceivingCIS2".to_string()),
+        ),
+        token_id: TOKEN_1,
+        amount:   TokenAmountU64(10),
+        data:     AdditionalData::empty(),
+    }]);
+    let update = chain
+        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(100000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.transfer".to_string()),
+            address:      source_address,
+            message:      OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
+        })
+        .expect("Transfer to contract");
+    let events = update
+        .events()
+        .filter(|(addr, _events)| *addr == contract_address)
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    let token_id = TokenIdVec(vec![TOKEN_1.0]);
+    assert_eq!(events, [Event::TokensReceived(TokensReceivedEvent {
+        contract: source_address,
+        token_id: token_id.clone(),
+        amount:   TokenAmountU64(10),
+        from:     ALICE_ADDR,
+    })]);
+    assert_eq!(view_received_tokens(&chain, contract_address), [ViewReceivedTokens {
+        contract: source_address,
+        token_id: token_id.clone(),
+        amount:   TokenAmountU64(10),
+    }]);
+
+    // Accounts can not call the hook.
+    let on_receiving = OnReceivingCis2Params {
+        token_id: token_id.clone(),
+        amount:   TokenAmountU64(10),
+        from:     ALICE_ADDR,
+        data:     AdditionalData::empty(),
+    };
+    let update = alice_update(
+        &mut chain,
+        contract_address,
+        "onReceivingCIS2",
+        OwnedParameter::from_serial(&on_receiving).expect("OnReceivingCis2 params"),
+    )
+    .expect_err("Call hook from an account");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::ContractOnly));
+
+    let recover_params = RecoverTokensParams {
+        contract: source_address,
+        token_id: token_id.clone(),
+        amount:   TokenAmountU64(4),
+        to:       Receiver::from_account(BOB),
+    };
+    let update = chain
+        .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.recoverTokens".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&recover_params).expect("RecoverTokens params"),
+        })
+        .expect_err("Recover tokens as Bob");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Unauthorized);
+
+    let update =
+        recover_tokens(&mut chain, contract_address, &recover_params).expect("Recover tokens");
+    let events = update
+        .events()
+        .filter(|(addr, _events)| *addr == contract_address)
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::TokensRecovered(TokensRecoveredEvent {
+        contract: source_address,
+        token_id: token_id.clone(),
+        amount:   TokenAmountU64(4),
+        to:       BOB_ADDR,
+    })]);
+
+    let balances = get_balances(&chain, source_address);
+    assert_eq!(balances.0, [TokenAmountU64(90), TokenAmountU64(4)]);
+    assert_eq!(view_received_tokens(&chain, contract_address), [ViewReceivedTokens {
+        contract: source_address,
+        token_id: token_id.clone(),
+        amount:   TokenAmountU64(6),
+    }]);
+
+    // Recovering more than the contract holds fails in the source contract.
+    let recover_params = RecoverTokensParams {
+        amount: TokenAmountU64(7),
+        ..recover_params
+    };
+    recover_tokens(&mut chain, contract_address, &recover_params)
+        .expect_err("Recover more than received");
+
+    let recover_params = RecoverTokensParams {
+        amount: TokenAmountU64(6),
+        ..recover_params
+    };
+    recover_tokens(&mut chain, contract_address, &recover_params).expect("Recover the rest");
+    assert_eq!(view_received_tokens(&chain, contract_address), []);
+}
+
+/// Initialize a second instance of the module, holding tokens of Alice.
+fn init_source_contract(chain: &mut Chain, module_reference: ModuleReference) -> ContractAddress {
+    let mut premint_tokens = BTreeMap::new();
+    premint_tokens.insert(TOKEN_1, (
+        MetadataUrl {
+            url:  TOKEN_1_METADATA.to_string(),
+            hash: None,
+        },
+        TokenParams {
+            amount:     TokenAmountU64(100),
+            max_supply: TokenAmountU64(1000),
+        },
+    ));
+    let init_params = InitParams {
+        premint_tokens,
+        premint_recipients: BTreeMap::new(),
+        owner: None,
+        metadata_base: None,
+        {% if roles %}
+        roles: Vec::new(),
+        {% endif %}
+        {% if wrapped_ccd %}
+        wrapped_metadata: wrapped_metadata(),
+        {% endif %}
+    };
+
+    chain
+        .contract_init(SIGNER, ALICE, Energy::from(10000), InitContractPayload {
+            amount:    Amount::zero(),
+            mod_ref:   module_reference,
+            init_name: OwnedContractName::new_unchecked("init_{{ contract_name }}".to_string()),
+            param:     OwnedParameter::from_serial(&init_params).expect("Init params"),
+        })
+        .expect("Initialize source contract")
+        .contract_address
+}
+
+/// Recover received tokens as Alice. The transfer in the source contract
+/// needs more energy than other updates.
+fn recover_tokens(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    params: &RecoverTokensParams,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(100000), UpdateContractPayload {
+        amount:       Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.recoverTokens".to_string()),
+        address:      contract_address,
+        message:      OwnedParameter::from_serial(params).expect("RecoverTokens params"),
+    })
+}
+
+/// Get the recorded amounts of received tokens.
+fn view_received_tokens(chain: &Chain, contract_address: ContractAddress) -> Vec<ViewReceivedTokens> {
+    chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.viewReceivedTokens".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke viewReceivedTokens")
+        .parse_return_value()
+        .expect("ViewReceivedTokens return value")
+}
+{% endif %}
+
+{% if wrapped_ccd %}
+/// Test wrapping and unwrapping CCD, checking after each step that the CCD
+/// balance of the contract equals the supply of wrapped CCD.
+#[test]
+fn test_wrap_unwrap() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+    assert_wrapped_backed(&chain, contract_address);
+
+    // Bob wraps CCD for Alice.
+    let params = WrapParams {
+        to:   Receiver::from_account(ALICE),
+        data: AdditionalData::empty(),
+    };
+    let update = wrap(&mut chain, contract_address, BOB, Amount::from_micro_ccd(100), &params)
+        .expect("Wrap CCD");
+    let events: Vec<Cis2Event<ContractTokenId, ContractTokenAmount>> = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect();
+    assert_eq!(events, [Cis2Event::Mint(MintEvent {
+        token_id: WRAPPED_TOKEN_ID,
+        amount:   TokenAmountU64(100),
+        owner:    ALICE_ADDR,
+    })]);
+    assert_eq!(wrapped_balances(&chain, contract_address), [TokenAmountU64(100), TokenAmountU64(0)]);
+    assert_wrapped_backed(&chain, contract_address);
+
+    // Wrapping to a contract without the hook fails as a whole.
+    let params = WrapParams {
+        to:   Receiver::from_contract(
+            contract_address,
+            OwnedEntrypointName::new_unchecked("missingHook".to_string()),
+        ),
+        data: AdditionalData::empty(),
+    };
+    wrap(&mut chain, contract_address, BOB, Amount::from_micro_ccd(50), &params)
+        .expect_err("Wrap to a contract without the hook");
+    assert_wrapped_backed(&chain, contract_address);
+
+    // Bob can not unwrap the wrapped CCD of Alice.
+    let params = UnwrapParams {
+        amount:   TokenAmountU64(30),
+        owner:    ALICE_ADDR,
+        receiver: Receiver::from_account(BOB),
+        data:     AdditionalData::empty(),
+    };
+    let update =
+        unwrap_as(&mut chain, contract_address, BOB, &params).expect_err("Unwrap as Bob");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Unauthorized);
+
+    // Alice unwraps CCD to Bob.
+    let bob_balance = chain.account_balance_available(BOB).expect("Bob exists");
+    unwrap_as(&mut chain, contract_address, ALICE, &params).expect("Unwrap CCD");
+    assert_eq!(
+        chain.account_balance_available(BOB),
+        Some(bob_balance + Amount::from_micro_ccd(30))
+    );
+    assert_eq!(wrapped_balances(&chain, contract_address), [TokenAmountU64(70), TokenAmountU64(0)]);
+    assert_wrapped_backed(&chain, contract_address);
+
+    // Unwrapping to `wrap` of the contract wraps the CCD again for Bob.
+    let params = UnwrapParams {
+        amount:   TokenAmountU64(20),
+        owner:    ALICE_ADDR,
+        receiver: Receiver::from_contract(
+            contract_address,
+            OwnedEntrypointName::new_unchecked("wrap".to_string()),
+        ),
+        data:     AdditionalData::from(to_bytes(&WrapParams {
+            to:   Receiver::from_account(BOB),
+            data: AdditionalData::empty(),
+        })),
+    };
+    unwrap_as(&mut chain, contract_address, ALICE, &params).expect("Unwrap to wrap");
+    assert_eq!(wrapped_balances(&chain, contract_address), [TokenAmountU64(50), TokenAmountU64(20)]);
+    assert_wrapped_backed(&chain, contract_address);
+
+    let params = UnwrapParams {
+        amount:   TokenAmountU64(51),
+        owner:    ALICE_ADDR,
+        receiver: Receiver::from_account(ALICE),
+        data:     AdditionalData::empty(),
+    };
+    let update = unwrap_as(&mut chain, contract_address, ALICE, &params)
+        .expect_err("Unwrap more than the balance");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::NoBalanceToBurn));
+    assert_wrapped_backed(&chain, contract_address);
+
+    {% if mintable %}
+    // The wrapped token is only minted by `wrap`.
+    let update = mint_as(&mut chain, contract_address, ALICE, WRAPPED_TOKEN_ID, 10)
+        .expect_err("Mint wrapped CCD");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::WrappedTokenReserved));
+    assert_wrapped_backed(&chain, contract_address);
+    {% endif %}
+
+    {% if pausable %}
+    let pause_params = PauseParams {
+        operations: PausedOperations {
+            wrap: true,
+            ..PausedOperations::default()
+        },
+        tokens:     Vec::new(),
+    };
+    chain
+        .contract_update(
+            SIGNER,
+            {% if roles %}
+            PAUSER,
+            PAUSER_ADDR,
+            {% else %}
+            ALICE,
+            ALICE_ADDR,
+            {% endif %}
+            Energy::from(10000),
+            UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.setPaused".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&pause_params).expect("Pause params"),
+        })
+        .expect("Pause wrapping");
+
+    // Wrapping is paused, unwrapping is not.
+    let params = WrapParams {
+        to:   Receiver::from_account(BOB),
+        data: AdditionalData::empty(),
+    };
+    let update = wrap(&mut chain, contract_address, BOB, Amount::from_micro_ccd(10), &params)
+        .expect_err("Wrap while paused");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::Paused));
+
+    let params = UnwrapParams {
+        amount:   TokenAmountU64(20),
+        owner:    BOB_ADDR,
+        receiver: Receiver::from_account(BOB),
+        data:     AdditionalData::empty(),
+    };
+    unwrap_as(&mut chain, contract_address, BOB, &params).expect("Unwrap while wrapping is paused");
+    assert_eq!(wrapped_balances(&chain, contract_address), [TokenAmountU64(50), TokenAmountU64(0)]);
+    assert_wrapped_backed(&chain, contract_address);
+    {% endif %}
+}
+
+/// The metadata URL of the wrapped CCD token.
+fn wrapped_metadata() -> MetadataUrl {
+    MetadataUrl {
+        url:  WRAPPED_METADATA.to_string(),
+        hash: None,
+    }
+}
+
+/// Wrap CCD sent by the sender.
+fn wrap(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    sender: AccountAddress,
+    amount: Amount,
+    params: &WrapParams,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(SIGNER, sender, Address::Account(sender), Energy::from(10000), UpdateContractPayload {
+        amount,
+        receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.wrap".to_string()),
+        address: contract_address,
+        message: OwnedParameter::from_serial(params).expect("Wrap params"),
+    })
+}
+
+/// Unwrap CCD as the sender.
+fn unwrap_as(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    sender: AccountAddress,
+    params: &UnwrapParams,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(SIGNER, sender, Address::Account(sender), Energy::from(100000), UpdateContractPayload {
+        amount:       Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.unwrap".to_string()),
+        address:      contract_address,
+        message:      OwnedParameter::from_serial(params).expect("Unwrap params"),
+    })
+}
+
+/// Get the wrapped CCD balances of Alice and Bob.
+fn wrapped_balances(chain: &Chain, contract_address: ContractAddress) -> Vec<ContractTokenAmount> {
+    let params = ContractBalanceOfQueryParams {
+        queries: vec![
+            BalanceOfQuery {
+                token_id: WRAPPED_TOKEN_ID,
+                address:  ALICE_ADDR,
+            },
+            BalanceOfQuery {
+                token_id: WRAPPED_TOKEN_ID,
+                address:  BOB_ADDR,
+            },
+        ],
+    };
+    let rv: ContractBalanceOfQueryResponse = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.balanceOf".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("BalanceOf params"),
+        })
+        .expect("Invoke balanceOf")
+        .parse_return_value()
+        .expect("BalanceOf return value");
+    rv.0
+}
+
+/// Check that the CCD balance of the contract equals the supply of wrapped CCD.
+fn assert_wrapped_backed(chain: &Chain, contract_address: ContractAddress) {
+    let supply: ContractTokenAmount = chain
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.wrappedSupply".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::empty(),
+        })
+        .expect("Invoke wrappedSupply")
+        .parse_return_value()
+        .expect("WrappedSupply return value");
+    assert_eq!(chain.contract_balance(contract_address), Some(Amount::from_micro_ccd(supply.0)));
+}
+{% endif %}
+
+{% if dividends %}
+/// Test that distributed CCD is shared by the holders of a token in proportion
+/// to their balances at the time of the distribution.
+#[test]
+fn test_dividends() {
+    let (mut chain, _keypairs, contract_address, _module_reference) =
+        initialize_chain_and_contract();
+
+    // Alice holds 75 and Bob 25 of the 100 units of `TOKEN_1`.
+    transfer_token_1(&mut chain, contract_address, ALICE, BOB, 25);
+
+    let update = distribute(&mut chain, contract_address, BOB, TOKEN_1, Amount::from_micro_ccd(1000))
+        .expect("Distribute dividends");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::DividendDistributed(DividendDistributedEvent {
+        token_id: TOKEN_1,
+        amount:   Amount::from_micro_ccd(1000),
+        from:     BOB_ADDR,
+    })]);
+    assert_eq!(claimable_dividends(&chain, contract_address), [
+        Amount::from_micro_ccd(750),
+        Amount::from_micro_ccd(250)
+    ]);
+
+    // Transfers settle the dividends accrued before them.
+    transfer_token_1(&mut chain, contract_address, ALICE, BOB, 25);
+    assert_eq!(claimable_dividends(&chain, contract_address), [
+        Amount::from_micro_ccd(750),
+        Amount::from_micro_ccd(250)
+    ]);
+
+    distribute(&mut chain, contract_address, BOB, TOKEN_1, Amount::from_micro_ccd(1000))
+        .expect("Distribute dividends");
+    assert_eq!(claimable_dividends(&chain, contract_address), [
+        Amount::from_micro_ccd(1250),
+        Amount::from_micro_ccd(750)
+    ]);
+
+    let update = distribute(&mut chain, contract_address, BOB, TokenIdU8(7), Amount::from_micro_ccd(1000))
+        .expect_err("Distribute dividends of an unknown token");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::InvalidTokenId);
+
+    // Alice claims her dividends to Bob's account.
+    let bob_balance = chain.account_balance_available(BOB).expect("Bob exists");
+    let params = ClaimParams {
+        token_id: TOKEN_1,
+        receiver: Receiver::from_account(BOB),
+        data:     AdditionalData::empty(),
+    };
+    let update = claim_as(&mut chain, contract_address, ALICE, &params).expect("Claim dividends");
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::DividendClaimed(DividendClaimedEvent {
+        token_id: TOKEN_1,
+        amount:   Amount::from_micro_ccd(1250),
+        holder:   ALICE_ADDR,
+    })]);
+    assert_eq!(
+        chain.account_balance_available(BOB),
+        Some(bob_balance + Amount::from_micro_ccd(1250))
+    );
+    assert_eq!(claimable_dividends(&chain, contract_address), [
+        Amount::zero(),
+        Amount::from_micro_ccd(750)
+    ]);
+
+    let update =
+        claim_as(&mut chain, contract_address, ALICE, &params).expect_err("Claim dividends twice");
+    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
+    assert_eq!(rv, ContractError::Custom(CustomContractError::NothingToClaim));
+
+    let params = ClaimParams {
+        token_id: TOKEN_1,
+        receiver: Receiver::from_account(BOB),
+        data:     AdditionalData::empty(),
+    };
+    claim_as(&mut chain, contract_address, BOB, &params).expect("Claim dividends");
+    assert_eq!(chain.contract_balance(contract_address), Some(Amount::zero()));
+
+    {% if mintable %}
+    // Minted tokens only share later distributions.
+    mint_as(&mut chain, contract_address, ALICE, TOKEN_1, 50).expect("Mint tokens");
+    distribute(&mut chain, contract_address, BOB, TOKEN_1, Amount::from_micro_ccd(1500))
+        .expect("Distribute dividends");
+    assert_eq!(claimable_dividends(&chain, contract_address), [
+        Amount::from_micro_ccd(1000),
+        Amount::from_micro_ccd(500)
+    ]);
+    {% endif %}
+
+    {% if burnable %}
+    // Burned tokens no longer share distributions.
+    let claimable = claimable_dividends(&chain, contract_address);
+    let burn_params = BurnParams {
+        owner:    BOB_ADDR,
+        token_id: TOKEN_1,
+        amount:   TokenAmountU64(50),
+    };
+    alice_update(
+        &mut chain,
+        contract_address,
+        "burn",
+        OwnedParameter::from_serial(&burn_params).expect("Burn params"),
+    )
+    .expect("Burn the tokens of Bob");
+    distribute(&mut chain, contract_address, BOB, TOKEN_1, Amount::from_micro_ccd(1000))
+        .expect("Distribute dividends");
+    assert_eq!(claimable_dividends(&chain, contract_address), [
+        claimable[0] + Amount::from_micro_ccd(1000),
+        claimable[1]
+    ]);
+    {% endif %}
+}
+
+/// Distribute CCD sent by the sender to the holders of a token.
+fn distribute(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    sender: AccountAddress,
+    token_id: ContractTokenId,
+    amount: Amount,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(SIGNER, sender, Address::Account(sender), Energy::from(10000), UpdateContractPayload {
+        amount,
+        receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.distribute".to_string()),
+        address: contract_address,
+        message: OwnedParameter::from_serial(&token_id).expect("Token ID"),
+    })
+}
+
+/// Claim the dividends of the sender.
+fn claim_as(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    sender: AccountAddress,
+    params: &ClaimParams,
+) -> Result<ContractInvokeSuccess, ContractInvokeError> {
+    chain.contract_update(SIGNER, sender, Address::Account(sender), Energy::from(10000), UpdateContractPayload {
+        amount:       Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.claim".to_string()),
+        address:      contract_address,
+        message:      OwnedParameter::from_serial(params).expect("Claim params"),
+    })
+}
+
+/// Get the dividends of `TOKEN_1` Alice and Bob can claim.
+fn claimable_dividends(chain: &Chain, contract_address: ContractAddress) -> Vec<Amount> {
+    let queries = vec![
+        DividendQuery {
+            token_id: TOKEN_1,
+            holder:   ALICE_ADDR,
+        },
+        DividendQuery {
+            token_id: TOKEN_1,
+            holder:   BOB_ADDR,
+        },
+    ];
     chain
-        .contract_update(
-            SIGNER,
-            {% if roles %}
-            PAUSER,
-            PAUSER_ADDR,
-            {% else %}
-            ALICE,
-            ALICE_ADDR,
-            {% endif %}
-            Energy::from(10000),
-            UpdateContractPayload {
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.setPaused".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.claimableDividends".to_string()),
             address:      contract_address,
-            message:      OwnedParameter::from_serial(&true).expect("Pause params"),
+            message:      OwnedParameter::from_serial(&queries).expect("Dividend queries"),
         })
-        .expect("Pause");
-
-    // Check that the contract is now paused.
-    assert_eq!(invoke_view(&mut chain, contract_address).paused, true);
+        .expect("Invoke claimableDividends")
+        .parse_return_value()
+        .expect("ClaimableDividends return value")
+}
+{% endif %}
 
-    // Unpause the contract.
+{% if dividends or snapshots %}
+/// Transfer `TOKEN_1` between accounts.
+fn transfer_token_1(
+    chain: &mut Chain,
+    contract_address: ContractAddress,
+    from: AccountAddress,
+    to: AccountAddress,
+    amount: u64,
+) {
+    let transfer_params = TransferParams::from(vec![concordium_cis2::Transfer {
+        from:     Address::Account(from),
+        to:       Receiver::from_account(to),
+        token_id: TOKEN_1,
+        amount:   TokenAmountU64(amount),
+        data:     AdditionalData::empty(),
+    }]);
     chain
-        .contract_update(
-            SIGNER,
-            {% if roles %}
-            PAUSER,
-            PAUSER_ADDR,
-            {% else %}
-            ALICE,
-            ALICE_ADDR,
-            {% endif %}
-            Energy::from(10000),
-            UpdateContractPayload {
+        .contract_update(SIGNER, from, Address::Account(from), Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.setPaused".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.transfer".to_string()),
             address:      contract_address,
-            message:      OwnedParameter::from_serial(&false).expect("Unpause params"),
+            message:      OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
         })
-        .expect("Unpause");
-    // Check that the contract is now unpaused.
-    assert_eq!(invoke_view(&mut chain, contract_address).paused, false);
+        .expect("Transfer tokens");
 }
+{% endif %}
 
-/// Test that only the PAUSER can pause/unpause the contract.
+{% if snapshots %}
+/// Test that balances and supplies are reported as they were when snapshots
+/// were taken.
 #[test]
-fn test_pause_unpause_unauthorized() {
+fn test_snapshots() {
     let (mut chain, _keypairs, contract_address, _module_reference) =
         initialize_chain_and_contract();
 
-    // Pause the contract as Bob, who is not the PAUSER.
     let update = chain
         .contract_update(SIGNER, BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.setPaused".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.snapshot".to_string()),
             address:      contract_address,
-            message:      OwnedParameter::from_serial(&true).expect("Pause params"),
+            message:      OwnedParameter::empty(),
         })
-        .expect_err("Pause");
-
-    // Check that the correct error is returned.
+        .expect_err("Take a snapshot as Bob");
     let rv: ContractError = update.parse_return_value().expect("ContractError return value");
     assert_eq!(rv, ContractError::Unauthorized);
-}
-
-/// Test that one can NOT call non-admin state-mutative functions (burn,
-/// mint, transfer, updateOperator) when the contract is paused.
-#[test]
-fn test_no_execution_of_state_mutative_functions_when_paused() {
-    let (mut chain, _keypairs, contract_address, _module_reference) =
-        initialize_chain_and_contract();
-
-    // Pause the contract.
-    chain
-        .contract_update(
-            SIGNER,
-            {% if roles %}
-            PAUSER,
-            PAUSER_ADDR,
-            {% else %}
-            ALICE,
-            ALICE_ADDR,
-            {% endif %}
-            Energy::from(10000),
-            UpdateContractPayload {
-            amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.setPaused".to_string()),
-            address:      contract_address,
-            message:      OwnedParameter::from_serial(&true).expect("Pause params"),
-        })
-        .expect("Pause");
 
-    // Try to transfer 1 token from Alice to Bob.
-    let transfer_params = TransferParams::from(vec![concordium_cis2::Transfer {
-        from:     ALICE_ADDR,
-        to:       Receiver::Account(BOB),
-        token_id: TOKEN_0,
-        amount:   TokenAmountU64(1),
-        data:     AdditionalData::empty(),
-    }]);
-    let update_transfer = chain
-        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
-            amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.transfer".to_string()),
-            address:      contract_address,
-            message:      OwnedParameter::from_serial(&transfer_params).expect("Transfer params"),
-        })
-        .expect_err("Transfer tokens");
-    assert_contract_paused_error(&update_transfer);
+    let update = alice_update(&mut chain, contract_address, "snapshot", OwnedParameter::empty())
+        .expect("Take a snapshot");
+    let snapshot_id: u64 = update.parse_return_value().expect("Snapshot ID");
+    assert_eq!(snapshot_id, 1);
+    let events = update
+        .events()
+        .flat_map(|(_addr, events)| events.iter().map(|e| e.parse().expect("Deserialize event")))
+        .collect::<Vec<Event>>();
+    assert_eq!(events, [Event::Snapshot(SnapshotEvent {
+        snapshot_id: 1,
+    })]);
 
-    // Try to add Bob as an operator for Alice.
-    let params = UpdateOperatorParams(vec![UpdateOperator {
-        update:   OperatorUpdate::Add,
-        operator: BOB_ADDR,
-    }]);
-    let update_operator = chain
-        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
-            amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.updateOperator".to_string()),
-            address:      contract_address,
-            message:      OwnedParameter::from_serial(&params).expect("UpdateOperator params"),
-        })
-        .expect_err("Update operator");
-    assert_contract_paused_error(&update_operator);
+    transfer_token_1(&mut chain, contract_address, ALICE, BOB, 30);
+    alice_update(&mut chain, contract_address, "snapshot", OwnedParameter::empty())
+        .expect("Take a snapshot");
+    transfer_token_1(&mut chain, contract_address, BOB, ALICE, 10);
+    // Nothing changes between snapshots 3 and 4.
+    alice_update(&mut chain, contract_address, "snapshot", OwnedParameter::empty())
+        .expect("Take a snapshot");
+    alice_update(&mut chain, contract_address, "snapshot", OwnedParameter::empty())
+        .expect("Take a snapshot");
+
+    assert_eq!(balances_at(&chain, contract_address, 1), Ok(vec![
+        TokenAmountU64(100),
+        TokenAmountU64(0)
+    ]));
+    assert_eq!(balances_at(&chain, contract_address, 2), Ok(vec![
+        TokenAmountU64(70),
+        TokenAmountU64(30)
+    ]));
+    assert_eq!(balances_at(&chain, contract_address, 3), Ok(vec![
+        TokenAmountU64(80),
+        TokenAmountU64(20)
+    ]));
+    assert_eq!(balances_at(&chain, contract_address, 4), Ok(vec![
+        TokenAmountU64(80),
+        TokenAmountU64(20)
+    ]));
+    assert_eq!(supply_at(&chain, contract_address, 1), Ok(TokenAmountU64(100)));
 
     {% if mintable %}
-    // Try to mint tokens.
-    let token_params = TokenParams {
-        amount: TokenAmountU64(10),
-        max_supply: TokenAmountU64(1000),
-    };
-
-    let mut mint_tokens = BTreeMap::new();
-    mint_tokens.insert(TOKEN_0, (
-        MetadataUrl {
-            url:  TOKEN_0_METADATA.to_string(),
-            hash: None,
-        }, token_params
-    ));
-
-    let params = MintParams {
-        owner:      ALICE_ADDR,
-        tokens:     mint_tokens,
-    };
-
-    let update_operator = chain
-        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
-            amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.mint".to_string()),
-            address:      contract_address,
-            message:      OwnedParameter::from_serial(&params).expect("Mint params"),
-        })
-        .expect_err("Update operator");
-    assert_contract_paused_error(&update_operator);
+    // Minting after a snapshot does not change the values at the snapshot.
+    mint_as(&mut chain, contract_address, ALICE, TOKEN_1, 50).expect("Mint tokens");
+    assert_eq!(balances_at(&chain, contract_address, 4), Ok(vec![
+        TokenAmountU64(80),
+        TokenAmountU64(20)
+    ]));
+    assert_eq!(supply_at(&chain, contract_address, 4), Ok(TokenAmountU64(100)));
+    alice_update(&mut chain, contract_address, "snapshot", OwnedParameter::empty())
+        .expect("Take a snapshot");
+    assert_eq!(supply_at(&chain, contract_address, 5), Ok(TokenAmountU64(150)));
     {% endif %}
 
     {% if burnable %}
-    // Try to burn tokens.
-    let params = BurnParams {
-        owner:    ALICE_ADDR,
-        amount:   TokenAmountU64(1),
-        token_id: TOKEN_0,
+    // Burning lowers the supply after the snapshot only.
+    let update = alice_update(&mut chain, contract_address, "snapshot", OwnedParameter::empty())
+        .expect("Take a snapshot");
+    let snapshot_id: u64 = update.parse_return_value().expect("Snapshot ID");
+    let supply = supply_at(&chain, contract_address, snapshot_id).expect("Supply at the snapshot");
+    let burn_params = BurnParams {
+        owner:    BOB_ADDR,
+        token_id: TOKEN_1,
+        amount:   TokenAmountU64(20),
     };
-
-    let update_operator = chain
-        .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
-            amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.burn".to_string()),
-            address:      contract_address,
-            message:      OwnedParameter::from_serial(&params).expect("Burn params"),
-        })
-        .expect_err("Update operator");
-    assert_contract_paused_error(&update_operator);
+    alice_update(
+        &mut chain,
+        contract_address,
+        "burn",
+        OwnedParameter::from_serial(&burn_params).expect("Burn params"),
+    )
+    .expect("Burn the tokens of Bob");
+    assert_eq!(supply_at(&chain, contract_address, snapshot_id), Ok(supply));
+    alice_update(&mut chain, contract_address, "snapshot", OwnedParameter::empty())
+        .expect("Take a snapshot");
+    assert_eq!(
+        supply_at(&chain, contract_address, snapshot_id + 1),
+        Ok(supply - TokenAmountU64(20))
+    );
     {% endif %}
+
+    for snapshot_id in [0, 100] {
+        assert_eq!(
+            balances_at(&chain, contract_address, snapshot_id),
+            Err(ContractError::Custom(CustomContractError::UnknownSnapshot))
+        );
+        assert_eq!(
+            supply_at(&chain, contract_address, snapshot_id),
+            Err(ContractError::Custom(CustomContractError::UnknownSnapshot))
+        );
+    }
 }
 
-/// Check that the returned error is `ContractPaused`.
-fn assert_contract_paused_error(update: &ContractInvokeError) {
-    let rv: ContractError = update.parse_return_value().expect("ContractError return value");
-    assert_eq!(rv, ContractError::Custom(CustomContractError::Paused));
+/// Get the balances of `TOKEN_1` of Alice and Bob at the snapshot.
+fn balances_at(
+    chain: &Chain,
+    contract_address: ContractAddress,
+    snapshot_id: u64,
+) -> Result<Vec<ContractTokenAmount>, ContractError> {
+    let queries = vec![
+        BalanceOfAtQuery {
+            owner:       ALICE_ADDR,
+            token_id:    TOKEN_1,
+            snapshot_id,
+        },
+        BalanceOfAtQuery {
+            owner:       BOB_ADDR,
+            token_id:    TOKEN_1,
+            snapshot_id,
+        },
+    ];
+    let invoke = chain.contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+        amount:       Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.balanceOfAt".to_string()),
+        address:      contract_address,
+        message:      OwnedParameter::from_serial(&queries).expect("BalanceOfAt queries"),
+    });
+    match invoke {
+        Ok(invoke) => Ok(invoke.parse_return_value().expect("BalanceOfAt return value")),
+        Err(error) => Err(error.parse_return_value().expect("ContractError return value")),
+    }
 }
 
-/// Get the result of the view entrypoint.
-fn invoke_view(chain: &mut Chain, contract_address: ContractAddress) -> ViewState {
-    let invoke = chain
-        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
-            amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.view".to_string()),
-            address:      contract_address,
-            message:      OwnedParameter::empty(),
-        })
-        .expect("Invoke view");
-    invoke.parse_return_value().expect("Return value")
+/// Get the supply of `TOKEN_1` at the snapshot.
+fn supply_at(
+    chain: &Chain,
+    contract_address: ContractAddress,
+    snapshot_id: u64,
+) -> Result<ContractTokenAmount, ContractError> {
+    let queries = vec![TotalSupplyAtQuery {
+        token_id:    TOKEN_1,
+        snapshot_id,
+    }];
+    let invoke = chain.contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+        amount:       Amount::zero(),
+        receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.totalSupplyAt".to_string()),
+        address:      contract_address,
+        message:      OwnedParameter::from_serial(&queries).expect("TotalSupplyAt queries"),
+    });
+    match invoke {
+        Ok(invoke) => {
+            let supplies: Vec<ContractTokenAmount> =
+                invoke.parse_return_value().expect("TotalSupplyAt return value");
+            Ok(supplies[0])
+        }
+        Err(error) => Err(error.parse_return_value().expect("ContractError return value")),
+    }
 }
 {% endif %}
 
@@ -870,6 +4456,36 @@ fn permit(
     entrypoint_name: String,
     keypairs: AccountKeys,
 ) -> ContractInvokeSuccess {
+    let param = signed_permit(chain, contract_address, payload, entrypoint_name, 0, None, &keypairs);
+
+    // Execute permit function.
+    chain
+        .contract_update(
+            Signer::with_one_key(),
+            BOB,
+            BOB_ADDR,
+            Energy::from(100000),
+            UpdateContractPayload {
+                amount:       Amount::zero(),
+                address:      contract_address,
+                receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.permit".to_string()),
+                message:      OwnedParameter::from_serial(&param)
+                    .expect("Should be a valid inut parameter"),
+            },
+        )
+        .expect("Should be able to exit permit token with permit")
+}
+
+/// Create a permit message signed by Alice.
+fn signed_permit(
+    chain: &Chain,
+    contract_address: ContractAddress,
+    payload: Vec<u8>,
+    entrypoint_name: String,
+    nonce: u64,
+    fee: Option<PermitFee>,
+    keypairs: &AccountKeys,
+) -> PermitParam {
     // The `viewMessageHash` function uses the same input parameter `PermitParam` as
     // the `permit` function. The `PermitParam` type includes a `signature` and
     // a `signer`. Because these two values (`signature` and `signer`) are not
@@ -885,21 +4501,33 @@ fn permit(
         },
         signer:    ALICE,
         message:   PermitMessage {
+            domain: PERMIT_DOMAIN.to_string(),
             timestamp: Timestamp::from_timestamp_millis(10_000_000_000),
             contract_address: ContractAddress::new(0, 0),
             entry_point: OwnedEntrypointName::new_unchecked(entrypoint_name),
-            nonce: 0,
+            nonce,
             payload,
+            fee,
         },
     };
+    sign_permit(chain, contract_address, &mut param, keypairs);
+    param
+}
 
+/// Sign the permit message of the param as Alice.
+fn sign_permit(
+    chain: &Chain,
+    contract_address: ContractAddress,
+    param: &mut PermitParam,
+    keypairs: &AccountKeys,
+) {
     // Get the message hash to be signed.
     let invoke = chain
         .contract_invoke(BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
             address:      contract_address,
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.viewMessageHash".to_string()),
-            message:      OwnedParameter::from_serial(&param)
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.viewMessageHash".to_string()),
+            message:      OwnedParameter::from_serial(&*param)
                 .expect("Should be a valid inut parameter"),
         })
         .expect("Should be able to query viewMessageHash");
@@ -908,23 +4536,84 @@ fn permit(
         from_bytes(&invoke.return_value).expect("Should return a valid result");
 
     param.signature = keypairs.sign_message(&to_bytes(&message_hash));
+}
 
-    // Execute permit function.
+/// The public key of the single key pair of the account keys.
+fn public_key_of(keypairs: &AccountKeys) -> PublicKeyEd25519 {
+    let keypair = keypairs
+        .keys
+        .values()
+        .next()
+        .and_then(|credential| credential.keys.values().next())
+        .expect("Key pair");
+    PublicKeyEd25519(keypair.public().to_bytes())
+}
+
+/// Sign a message with the single key pair of the account keys.
+fn sign_ed25519(keypairs: &AccountKeys, message: &[u8]) -> SignatureEd25519 {
+    let signatures = keypairs.sign_message(message);
+    match &signatures.sigs[&0].sigs[&0] {
+        concordium_std::Signature::Ed25519(signature) => *signature,
+        _ => panic!("Key pairs sign with Ed25519"),
+    }
+}
+
+/// Create a permit message signed by the public key of Alice.
+fn signed_public_key_permit(
+    chain: &Chain,
+    contract_address: ContractAddress,
+    payload: Vec<u8>,
+    entrypoint_name: String,
+    keypairs: &AccountKeys,
+) -> PermitPublicKeyParam {
+    let mut param = PermitPublicKeyParam {
+        signature: DUMMY_SIGNATURE,
+        signer:    public_key_of(keypairs),
+        message:   PermitMessage {
+            domain: PERMIT_DOMAIN.to_string(),
+            timestamp: Timestamp::from_timestamp_millis(10_000_000_000),
+            contract_address: ContractAddress::new(0, 0),
+            entry_point: OwnedEntrypointName::new_unchecked(entrypoint_name),
+            nonce: 0,
+            payload,
+            fee: None,
+        },
+    };
+
+    let message_hash: [u8; 32] = chain
+        .contract_invoke(BOB, BOB_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            address:      contract_address,
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.viewPublicKeyMessageHash".to_string()),
+            message:      OwnedParameter::from_serial(&param).expect("PermitPublicKey params"),
+        })
+        .expect("Invoke viewPublicKeyMessageHash")
+        .parse_return_value()
+        .expect("Message hash return value");
+
+    param.signature = sign_ed25519(keypairs, &message_hash);
+    param
+}
+
+/// Get the next nonces of the accounts.
+fn nonce_of(
+    chain: &Chain,
+    contract_address: ContractAddress,
+    accounts: Vec<AccountAddress>,
+) -> NonceOfQueryResponse {
+    let params = VecOfAccountAddresses {
+        queries: accounts,
+    };
     chain
-        .contract_update(
-            Signer::with_one_key(),
-            BOB,
-            BOB_ADDR,
-            Energy::from(10000),
-            UpdateContractPayload {
-                amount:       Amount::zero(),
-                address:      contract_address,
-                receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.permit".to_string()),
-                message:      OwnedParameter::from_serial(&param)
-                    .expect("Should be a valid inut parameter"),
-            },
-        )
-        .expect("Should be able to exit permit token with permit")
+        .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
+            amount:       Amount::zero(),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.nonceOf".to_string()),
+            address:      contract_address,
+            message:      OwnedParameter::from_serial(&params).expect("NonceOf params"),
+        })
+        .expect("Invoke nonceOf")
+        .parse_return_value()
+        .expect("NonceOf return value")
 }
 
 /// Check if Bob is an operator of Alice.
@@ -940,7 +4629,7 @@ fn operator_of(chain: &Chain, contract_address: ContractAddress) -> OperatorOfQu
     let invoke = chain
         .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.operatorOf".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.operatorOf".to_string()),
             address:      contract_address,
             message:      OwnedParameter::from_serial(&operator_of_params)
                 .expect("OperatorOf params"),
@@ -973,7 +4662,7 @@ fn get_balances(
     let invoke = chain
         .contract_invoke(ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.balanceOf".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.balanceOf".to_string()),
             address:      contract_address,
             message:      OwnedParameter::from_serial(&balance_of_params)
                 .expect("BalanceOf params"),
@@ -1038,14 +4727,23 @@ fn initialize_chain_and_contract() -> (Chain, AccountKeys, ContractAddress, Modu
     ));
 
     let init_params = InitParams {
-        premint_tokens: premint_tokens,
+        premint_tokens,
+        premint_recipients: BTreeMap::new(),
+        owner: None,
+        metadata_base: None,
+        {% if roles %}
+        roles: Vec::new(),
+        {% endif %}
+        {% if wrapped_ccd %}
+        wrapped_metadata: wrapped_metadata(),
+        {% endif %}
     };
 
     let init = chain
         .contract_init(SIGNER, ALICE, Energy::from(10000), InitContractPayload {
             amount:    Amount::zero(),
             mod_ref:   deployment.module_reference,
-            init_name: OwnedContractName::new_unchecked("init_mint_wizard_{{ code }}_V{{ version }}".to_string()),
+            init_name: OwnedContractName::new_unchecked("init_{{ contract_name }}".to_string()),
             param:     OwnedParameter::from_serial(&init_params).expect("Init params"),
         })
         .expect("Initialize contract");
@@ -1056,12 +4754,13 @@ fn initialize_chain_and_contract() -> (Chain, AccountKeys, ContractAddress, Modu
     let grant_role_params = GrantRoleParams {
         address: UPGRADER_ADDR,
         role:    Roles::UPGRADER,
+        expiry:  None,
     };
 
     let _update = chain
         .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.grantRole".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.grantRole".to_string()),
             address:      init.contract_address,
             message:      OwnedParameter::from_serial(&grant_role_params)
                 .expect("GrantRole params"),
@@ -1073,12 +4772,13 @@ fn initialize_chain_and_contract() -> (Chain, AccountKeys, ContractAddress, Modu
     let grant_role_params = GrantRoleParams {
         address: PAUSER_ADDR,
         role:    Roles::PAUSER,
+        expiry:  None,
     };
 
     let _update = chain
         .contract_update(SIGNER, ALICE, ALICE_ADDR, Energy::from(10000), UpdateContractPayload {
             amount:       Amount::zero(),
-            receive_name: OwnedReceiveName::new_unchecked("mint_wizard_{{ code }}_V{{ version }}.grantRole".to_string()),
+            receive_name: OwnedReceiveName::new_unchecked("{{ contract_name }}.grantRole".to_string()),
             address:      init.contract_address,
             message:      OwnedParameter::from_serial(&grant_role_params)
                 .expect("GrantRole params"),